@@ -0,0 +1,203 @@
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+
+use chessboard::board::*;
+use chessboard::moves::*;
+use chessboard::search::*;
+
+use crate::uci::{self, Go};
+
+/// Limits of the options, as announced to the GUI.
+const MAX_HASH_MB: usize = 65536;
+const MAX_THREADS: usize = 256;
+
+/// What the GUI said while a search was running, read by the search thread.
+#[derive(Default)]
+struct Signals {
+    /// Set by stop. The search stops and sends its best move.
+    stop: AtomicBool,
+    /// Set by ponderhit. The opponent played the move that was pondered on, so the search goes on
+    /// with the limits of the go command.
+    ponderhit: AtomicBool
+}
+
+/// The state of the engine between commands.
+pub struct Engine {
+    board: Board,
+    /// The searcher is moved to the search thread while it runs, and is None until it comes back.
+    searcher: Option<Searcher>,
+    search: Option<JoinHandle<Searcher>>,
+    /// The stop flag of the searcher, which stays the same for its whole life.
+    stop: Arc<AtomicBool>,
+    signals: Arc<Signals>,
+    /// Set while the search ponders, until ponderhit or stop.
+    pondering: bool
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        let searcher: Searcher = Searcher::new();
+        return Engine {
+            board: Board::from_fen(START_FEN).unwrap(),
+            stop: searcher.stop_flag(),
+            searcher: Some(searcher),
+            search: None,
+            signals: Arc::new(Signals::default()),
+            pondering: false
+        };
+    }
+
+    /// Handles one line sent by the GUI. Returns false once the engine has to quit.
+    pub fn handle(&mut self, line: &str) -> bool {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.first().copied() {
+            Some("uci") => {
+                println!("id name cb-uci {}", env!("CARGO_PKG_VERSION"));
+                println!("id author the chessboard developers");
+                println!("option name Hash type spin default {} min 1 max {}", DEFAULT_SIZE_MB,
+                    MAX_HASH_MB);
+                println!("option name Threads type spin default 1 min 1 max {}", MAX_THREADS);
+                println!("option name MultiPV type spin default 1 min 1 max {}", MAX_NUM_MOVES);
+                println!("option name Ponder type check default false");
+                println!("uciok");
+            },
+            Some("isready") => println!("readyok"),
+            Some("ucinewgame") => {
+                self.searcher().clear();
+                self.board = Board::from_fen(START_FEN).unwrap();
+            },
+            Some("position") => {
+                let args: &str = line.trim_start()["position".len()..].trim();
+                match Board::from_uci(args) {
+                    Ok(board) => self.board = board,
+                    Err(err) => println!("info string {}", err)
+                }
+            },
+            Some("go") => self.go(uci::parse_go(&tokens[1..])),
+            Some("stop") => {
+                self.pondering = false;
+                self.signal(&self.signals.stop);
+            },
+            Some("ponderhit") if self.pondering => {
+                self.pondering = false;
+                self.signal(&self.signals.ponderhit);
+            },
+            Some("ponderhit") => (),
+            Some("setoption") => self.set_option(&tokens[1..]),
+            Some("quit") => {
+                self.finish_search();
+                return false;
+            },
+            Some("debug" | "register") | None => (),
+            Some(command) => println!("info string unknown command: {}", command)
+        }
+        return true;
+    }
+
+    /// Stops the search if one is running and takes the searcher back from it.
+    pub fn finish_search(&mut self) {
+        self.signal(&self.signals.stop);
+        self.pondering = false;
+        if let Some(search) = self.search.take() {
+            self.searcher = Some(search.join().unwrap());
+        }
+    }
+
+    /// Gets the searcher, stopping the search first if one is running.
+    fn searcher(&mut self) -> &mut Searcher {
+        self.finish_search();
+        return self.searcher.as_mut().unwrap();
+    }
+
+    /// Sets one of the signals, and wakes the search thread up in case it is waiting for it.
+    fn signal(&self, signal: &AtomicBool) {
+        signal.store(true, Ordering::SeqCst);
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(search) = &self.search {
+            search.thread().unpark();
+        }
+    }
+
+    fn set_option(&mut self, args: &[&str]) {
+        let (name, value): (String, String) = match uci::parse_option(args) {
+            Some(option) => option,
+            None => return println!("info string malformed setoption")
+        };
+        let number: Option<usize> = value.parse().ok();
+        match (name.to_lowercase().as_str(), number) {
+            ("hash", Some(mb)) => self.searcher().set_hash_size(mb.clamp(1, MAX_HASH_MB)),
+            ("threads", Some(threads)) => self.searcher().set_threads(threads.min(MAX_THREADS)),
+            ("multipv", Some(lines)) => self.searcher().set_multipv(lines.min(MAX_NUM_MOVES)),
+            ("ponder", _) => (),
+            _ => println!("info string unknown option {} or invalid value {}", name, value)
+        }
+    }
+
+    /// Starts searching the current position on another thread, which sends the best move when
+    /// it is done.
+    fn go(&mut self, go: Go) {
+        self.finish_search();
+        let mut searcher: Searcher = self.searcher.take().unwrap();
+        let mut board: Board = self.board.clone();
+        let signals: Arc<Signals> = Arc::new(Signals::default());
+        let stop: Arc<AtomicBool> = self.stop.clone();
+        self.signals = signals.clone();
+        self.pondering = go.ponder;
+        self.search = Some(thread::spawn(move || {
+            let result: SearchResult = run_search(&mut searcher, &mut board, &go, &signals, &stop);
+            println!("{}", uci::format_bestmove(&result));
+            return searcher;
+        }));
+    }
+}
+
+/// Runs the search for a go command. While pondering, the search ignores the limits until
+/// ponderhit, and then starts over with them; the transposition table keeps what was found so
+/// far. An infinite or pondering search that ends on its own, e.g. because it found a mate,
+/// doesn't return before the GUI says so.
+fn run_search(
+    searcher: &mut Searcher,
+    board: &mut Board,
+    go: &Go,
+    signals: &Signals,
+    stop: &AtomicBool
+) -> SearchResult {
+    let stopped = || signals.stop.load(Ordering::SeqCst);
+    if go.ponder {
+        let pondering: SearchLimits = SearchLimits { infinite: true, ..Default::default() };
+        let result: SearchResult = search_until(searcher, board, &pondering, stop, || {
+            stopped() || signals.ponderhit.load(Ordering::SeqCst)
+        });
+        if stopped() {
+            return result;
+        }
+    }
+    return search_until(searcher, board, &go.limits, stop, stopped);
+}
+
+/// Searches with the given limits, reporting every iteration, until the limits are reached or
+/// released returns true. Infinite searches always wait for released.
+fn search_until(
+    searcher: &mut Searcher,
+    board: &mut Board,
+    limits: &SearchLimits,
+    stop: &AtomicBool,
+    released: impl Fn() -> bool
+) -> SearchResult {
+    // The searcher clears the stop flag when it starts, so a signal that came in before that is
+    // passed on after the first iteration.
+    let result: SearchResult = searcher.search_limits(board, limits, |iteration| {
+        for info in uci::format_info(iteration) {
+            println!("{}", info);
+        }
+        if released() {
+            stop.store(true, Ordering::SeqCst);
+        }
+    });
+    while limits.infinite && !released() {
+        thread::park();
+    }
+    return result;
+}
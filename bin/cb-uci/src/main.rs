@@ -0,0 +1,25 @@
+#![allow(clippy::needless_return, clippy::new_without_default)]
+
+use std::io::{self, BufRead};
+
+mod engine;
+mod uci;
+
+use engine::Engine;
+
+/// Speaks the Universal Chess Interface over stdin and stdout, so that the engine can be used by
+/// chess GUIs. Searches run on their own thread, so commands like stop are handled while the
+/// engine is thinking. The end of stdin counts as quit.
+fn main() {
+    let mut engine: Engine = Engine::new();
+    for line in io::stdin().lock().lines() {
+        let line: String = match line {
+            Ok(line) => line,
+            Err(_) => break
+        };
+        if !engine.handle(&line) {
+            return;
+        }
+    }
+    engine.finish_search();
+}
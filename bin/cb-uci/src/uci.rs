@@ -0,0 +1,96 @@
+
+use std::time::Duration;
+
+use chessboard::moves::*;
+use chessboard::search::*;
+
+/// A go command: when to stop the search, and whether it starts by pondering on the opponent's
+/// time.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Go {
+    pub limits: SearchLimits,
+    pub ponder: bool
+}
+
+/// Parses the arguments of a go command. Values that aren't numbers are ignored, and so are the
+/// moves of searchmoves. Negative times, which some GUIs send once a clock ran out, count as no
+/// time left.
+pub fn parse_go(args: &[&str]) -> Go {
+    let mut go: Go = Go::default();
+    let mut tokens = args.iter();
+    while let Some(token) = tokens.next() {
+        let mut value = || tokens.next().and_then(|value| value.parse::<i64>().ok());
+        let mut millis = || value().map(|ms| Duration::from_millis(ms.max(0) as u64));
+        match *token {
+            "infinite" => go.limits.infinite = true,
+            "ponder" => go.ponder = true,
+            "wtime" => go.limits.wtime = millis(),
+            "btime" => go.limits.btime = millis(),
+            "winc" => go.limits.winc = millis(),
+            "binc" => go.limits.binc = millis(),
+            "movetime" => go.limits.movetime = millis(),
+            "movestogo" => go.limits.movestogo = value().map(|moves| moves.max(1) as u32),
+            "depth" => go.limits.depth = value().map(|depth| depth.clamp(1, MAX_PLY as i64) as u8),
+            "nodes" => go.limits.nodes = value().map(|nodes| nodes.max(1) as u64),
+            "mate" => go.limits.mate = value().map(|moves| moves.clamp(1, MAX_PLY as i64) as u32),
+            _ => ()
+        }
+    }
+    return go;
+}
+
+/// Parses the arguments of a setoption command into the name and the value of the option. The
+/// value is empty for buttons.
+pub fn parse_option(args: &[&str]) -> Option<(String, String)> {
+    let name_at: usize = args.iter().position(|token| *token == "name")?;
+    let value_at: usize = args.iter().position(|token| *token == "value").unwrap_or(args.len());
+    if value_at <= name_at + 1 {
+        return None;
+    }
+    let name: String = args[name_at + 1..value_at].join(" ");
+    let value: String = args.get(value_at + 1..).unwrap_or_default().join(" ");
+    return Some((name, value));
+}
+
+/// Formats a score the way info lines give it: in centipawns, or in moves until mate, negative if
+/// the engine is getting mated.
+pub fn format_score(score: i32) -> String {
+    return if is_mate_score(score) {
+        format!("mate {}", mate_distance(score))
+    } else {
+        format!("cp {}", score)
+    };
+}
+
+/// Formats the info lines for an iteration of the search, one for every line of a MultiPV
+/// search.
+pub fn format_info(result: &SearchResult) -> Vec<String> {
+    let millis: u128 = result.time.as_millis();
+    let nps: u128 = result.nodes as u128 * 1000 / millis.max(1);
+    return result.lines.iter().enumerate().map(|(i, line)| {
+        format!(
+            "info depth {} seldepth {} multipv {} score {} nodes {} nps {} hashfull {} time {} \
+             pv {}",
+            line.depth,
+            line.seldepth,
+            i + 1,
+            format_score(line.score),
+            result.nodes,
+            nps,
+            result.hashfull,
+            millis,
+            line.to_uci()
+        )
+    }).collect();
+}
+
+/// Formats the final answer to a go command, with the expected reply to ponder on if the
+/// principal variation has one. A position without legal moves gets the null move.
+pub fn format_bestmove(result: &SearchResult) -> String {
+    let best_move: String = result.best_move.as_ref()
+        .map_or("0000".to_string(), Move::to_long_algbr);
+    return match result.pv.get(1) {
+        Some(reply) => format!("bestmove {} ponder {}", best_move, reply.to_long_algbr()),
+        None => format!("bestmove {}", best_move)
+    };
+}
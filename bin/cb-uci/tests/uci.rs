@@ -0,0 +1,293 @@
+#![allow(clippy::needless_return)]
+
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chessboard::board::*;
+
+/// How long to wait for an answer that should come, so that a broken engine fails the test
+/// instead of hanging it.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The engine binary, talked to over its stdin and stdout.
+struct Engine {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>
+}
+
+impl Engine {
+    fn start() -> Self {
+        let mut child: Child = Command::new(env!("CARGO_BIN_EXE_cb-uci"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdin: ChildStdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in stdout.lines() {
+                if sender.send(line.unwrap()).is_err() {
+                    return;
+                }
+            }
+        });
+        return Engine { child, stdin, lines };
+    }
+
+    fn send(&mut self, command: &str) {
+        writeln!(self.stdin, "{}", command).unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    /// Reads lines until one starts with the prefix, and returns all of them.
+    fn read_until(&mut self, prefix: &str) -> Vec<String> {
+        let mut lines: Vec<String> = vec![];
+        let start: Instant = Instant::now();
+        loop {
+            let line: String = self.lines.recv_timeout(TIMEOUT.saturating_sub(start.elapsed()))
+                .unwrap_or_else(|_| panic!("no {} after {:?}", prefix, lines));
+            let done: bool = line.starts_with(prefix);
+            lines.push(line);
+            if done {
+                return lines;
+            }
+        }
+    }
+
+    /// Checks that nothing starting with the prefix comes for a while.
+    fn expect_silence(&mut self, prefix: &str, time: Duration) {
+        let start: Instant = Instant::now();
+        loop {
+            match self.lines.recv_timeout(time.saturating_sub(start.elapsed())) {
+                Ok(line) => assert!(!line.starts_with(prefix), "unexpected {}", line),
+                Err(RecvTimeoutError::Timeout) => return,
+                Err(RecvTimeoutError::Disconnected) => panic!("the engine exited")
+            }
+        }
+    }
+
+    /// Sends a go command and returns the lines up to the best move.
+    fn go(&mut self, command: &str) -> Vec<String> {
+        self.send(command);
+        return self.read_until("bestmove");
+    }
+
+    fn quit(mut self) {
+        self.send("quit");
+        assert!(self.child.wait().unwrap().success());
+    }
+}
+
+/// Gets the value of a field of an info line, e.g. the number after "depth".
+fn field<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let mut tokens = line.split_whitespace();
+    tokens.position(|token| token == name)?;
+    return tokens.next();
+}
+
+/// Gets the best move of the last line, and the move to ponder on if there is one.
+fn bestmove(lines: &[String]) -> (String, Option<String>) {
+    let tokens: Vec<&str> = lines.last().unwrap().split_whitespace().collect();
+    assert_eq!(tokens[0], "bestmove");
+    return (tokens[1].to_string(), tokens.get(3).map(|mv| mv.to_string()));
+}
+
+/// Checks that a move can be played after the moves of a UCI position string.
+fn is_legal(position: &str, algbr: &str) -> bool {
+    let moves: &str = if position.contains(" moves ") { "" } else { " moves" };
+    return Board::from_uci(&format!("{}{} {}", position, moves, algbr)).is_ok();
+}
+
+#[test]
+fn test_handshake() {
+    let mut engine: Engine = Engine::start();
+    engine.send("uci");
+    let lines: Vec<String> = engine.read_until("uciok");
+    assert!(lines[0].starts_with("id name "));
+    for option in [ "Hash", "Threads", "MultiPV", "Ponder" ] {
+        let prefix: String = format!("option name {} type ", option);
+        assert!(lines.iter().any(|line| line.starts_with(&prefix)), "no option {}", option);
+    }
+
+    engine.send("isready");
+    assert_eq!(engine.read_until("readyok"), vec![ "readyok" ]);
+    engine.send("ucinewgame");
+    engine.send("isready");
+    assert_eq!(engine.read_until("readyok"), vec![ "readyok" ]);
+    engine.quit();
+}
+
+#[test]
+fn test_go_depth() {
+    let mut engine: Engine = Engine::start();
+    let position: &str = "startpos moves e2e4 e7e5";
+    engine.send(&format!("position {}", position));
+    let lines: Vec<String> = engine.go("go depth 4");
+
+    // Every iteration is reported.
+    let infos: Vec<&String> = lines.iter().filter(|line| line.starts_with("info ")).collect();
+    assert_eq!(infos.len(), 4);
+    for (i, info) in infos.iter().enumerate() {
+        assert_eq!(field(info, "depth"), Some((i + 1).to_string().as_str()));
+        assert!(field(info, "score") == Some("cp"), "{}", info);
+        for name in [ "seldepth", "nodes", "nps", "hashfull", "time" ] {
+            assert!(field(info, name).unwrap().parse::<u64>().is_ok(), "{}", info);
+        }
+        assert!(is_legal(position, field(info, "pv").unwrap()));
+    }
+
+    let (best, ponder): (String, Option<String>) = bestmove(&lines);
+    assert_eq!(field(infos[3], "pv"), Some(best.as_str()));
+    assert!(is_legal(position, &best));
+    assert!(is_legal(&format!("{} {}", position, best), &ponder.unwrap()));
+    engine.quit();
+}
+
+#[test]
+fn test_position() {
+    let mut engine: Engine = Engine::start();
+    engine.send("position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+    let lines: Vec<String> = engine.go("go depth 3");
+    assert_eq!(bestmove(&lines).0, "a1a8");
+    assert!(lines.iter().any(|line| line.contains(" score mate 1 ")));
+
+    // The side to move is getting mated.
+    engine.send("position fen 6k1/5ppp/8/8/8/8/8/R5K1 b - - 0 1 moves g8h8 a1a8");
+    let lines: Vec<String> = engine.go("go depth 3");
+    assert_eq!(bestmove(&lines).0, "0000");
+
+    // A position that can't be parsed is reported and doesn't replace the last one.
+    engine.send("position startpos moves e2e5");
+    assert!(engine.read_until("info string").last().unwrap().contains("e2e5"));
+    engine.send("position fen 8/8/8 w - - 0 1");
+    engine.read_until("info string");
+    let lines: Vec<String> = engine.go("go depth 1");
+    assert_eq!(bestmove(&lines).0, "0000");
+
+    engine.send("position startpos moves g1f3 g8f6 f3g1 f6g8");
+    let lines: Vec<String> = engine.go("go depth 2");
+    assert!(is_legal("startpos", &bestmove(&lines).0));
+    engine.quit();
+}
+
+#[test]
+fn test_go_limits() {
+    let mut engine: Engine = Engine::start();
+    engine.send("position startpos");
+
+    let start: Instant = Instant::now();
+    engine.go("go movetime 300");
+    assert!(start.elapsed() < Duration::from_secs(3));
+
+    let lines: Vec<String> = engine.go("go nodes 5000");
+    let info: &String = lines.iter().rev().find(|line| line.starts_with("info ")).unwrap();
+    assert!(field(info, "nodes").unwrap().parse::<u64>().unwrap() < 10000);
+
+    let start: Instant = Instant::now();
+    engine.go("go wtime 2000 btime 2000 winc 10 binc 10 movestogo 20");
+    assert!(start.elapsed() < Duration::from_secs(2));
+
+    // A clock that ran out still gives a move.
+    engine.go("go wtime -50 btime 1000");
+
+    engine.send("position fen 3k4/8/8/8/8/8/R7/6KR w - - 0 1");
+    let lines: Vec<String> = engine.go("go mate 2");
+    assert!(lines.iter().any(|line| line.contains(" score mate 2 ")));
+    engine.quit();
+}
+
+#[test]
+fn test_stop() {
+    let mut engine: Engine = Engine::start();
+    engine.send("position startpos");
+    engine.send("go infinite");
+    engine.expect_silence("bestmove", Duration::from_millis(300));
+    engine.send("isready");
+    engine.read_until("readyok");
+    let lines: Vec<String> = engine.go("stop");
+    assert!(is_legal("startpos", &bestmove(&lines).0));
+
+    // An infinite search that is over early still waits for stop.
+    engine.send("position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+    engine.send("go infinite");
+    engine.expect_silence("bestmove", Duration::from_millis(300));
+    assert_eq!(bestmove(&engine.go("stop")).0, "a1a8");
+
+    // A stop without a search does nothing.
+    engine.send("stop");
+    engine.send("isready");
+    assert_eq!(engine.read_until("readyok"), vec![ "readyok" ]);
+    engine.quit();
+}
+
+#[test]
+fn test_ponder() {
+    let mut engine: Engine = Engine::start();
+    engine.send("setoption name Ponder value true");
+    engine.send("position startpos moves e2e4 e7e5");
+
+    // Pondering ignores the clock until ponderhit.
+    engine.send("go ponder wtime 200 btime 200");
+    engine.expect_silence("bestmove", Duration::from_millis(500));
+    let start: Instant = Instant::now();
+    let lines: Vec<String> = engine.go("ponderhit");
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert!(is_legal("startpos moves e2e4 e7e5", &bestmove(&lines).0));
+
+    engine.send("go ponder movetime 100");
+    engine.expect_silence("bestmove", Duration::from_millis(300));
+    engine.go("stop");
+
+    // A ponderhit without pondering doesn't stop the search.
+    engine.send("go depth 3");
+    engine.send("ponderhit");
+    let lines: Vec<String> = engine.read_until("bestmove");
+    assert!(lines.iter().any(|line| field(line, "depth") == Some("3")));
+    engine.quit();
+}
+
+#[test]
+fn test_options() {
+    let mut engine: Engine = Engine::start();
+    engine.send("setoption name MultiPV value 3");
+    engine.send("setoption name Threads value 2");
+    engine.send("setoption name Hash value 8");
+    engine.send("position startpos");
+    let lines: Vec<String> = engine.go("go depth 3");
+    let last: Vec<&String> = lines.iter()
+        .filter(|line| field(line, "depth") == Some("3"))
+        .collect();
+    assert_eq!(last.len(), 3);
+    for (i, line) in last.iter().enumerate() {
+        assert_eq!(field(line, "multipv"), Some((i + 1).to_string().as_str()));
+    }
+    let first_moves: Vec<&str> = last.iter().map(|line| field(line, "pv").unwrap()).collect();
+    assert!(first_moves[0] != first_moves[1] && first_moves[1] != first_moves[2]);
+    assert_eq!(bestmove(&lines).0, first_moves[0]);
+
+    engine.send("setoption name Hash value lots");
+    assert!(engine.read_until("info string").last().unwrap().contains("Hash"));
+    engine.send("setoption name Contempt value 10");
+    engine.read_until("info string");
+    engine.send("setoption Hash");
+    engine.read_until("info string");
+    engine.send("flip");
+    assert!(engine.read_until("info string").last().unwrap().contains("flip"));
+    engine.quit();
+}
+
+#[test]
+fn test_end_of_input() {
+    let mut engine: Engine = Engine::start();
+    engine.send("position startpos");
+    engine.send("go infinite");
+    drop(engine.stdin);
+    assert!(engine.child.wait().unwrap().success());
+    let lines: Vec<String> = engine.lines.iter().collect();
+    assert!(lines.last().unwrap().starts_with("bestmove "));
+}
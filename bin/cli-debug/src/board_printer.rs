@@ -1,15 +1,10 @@
 
-use chessboard::board::*;
-use chessboard::moves::*;
 use crossterm::style::{Color, Stylize};
 
 const LIGHT: Color = Color::Rgb { r: 154, g: 175, b: 219 };
 const DARK: Color = Color::Rgb { r: 40, g: 54, b: 84 };
 const BORDER: Color = Color::Rgb { r: 0, g: 0, b: 0 };
 
-const V: [&str; 8] = [ " ","\u{2581}","\u{2582}","\u{2583}","\u{2584}",
-    "\u{2585}","\u{2586}","\u{2587}"
-];
 const H: [&str; 8] = [ " ","\u{258F}","\u{258E}","\u{258D}","\u{258C}",
     "\u{258B}","\u{258A}","\u{2589}"
 ];
@@ -39,7 +34,7 @@ impl BoardDrawBuffer {
         buff.data[14] = BoardDrawBuffer::make_border(false, true);
         buff.data[15] = BoardDrawBuffer::make_line(true);
         buff.data[16] = BoardDrawBuffer::make_border(true, true);
-        return buff;
+        buff
     }
 
     fn make_border(top_is_edge: bool, invert: bool) -> String {
@@ -59,18 +54,16 @@ impl BoardDrawBuffer {
             invedge = invedge.with(BORDER);
 
             if invert {
-                let temp = edge;
-                edge = invedge;
-                invedge = temp;
+                std::mem::swap(&mut edge, &mut invedge);
             }
         }
 
         let solid = " ".on(BORDER);
-        return format!(
+        format!(
             "{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}",
             s0, solid, edge, solid, invedge, solid, edge, solid, invedge, 
             solid, edge, solid, invedge, solid, edge, solid, invedge, solid, e0
-        );
+        )
     }
 
     fn make_line(invert: bool) -> String {
@@ -84,9 +77,7 @@ impl BoardDrawBuffer {
         let mut end = edge.reverse().with(BORDER);
 
         if invert {
-            let temp = inner;
-            inner = invinner;
-            invinner = temp;
+            std::mem::swap(&mut inner, &mut invinner);
             edge = edge.reverse();
             start = invedge.with(BORDER);
             end = invedge.on(BORDER);
@@ -94,11 +85,11 @@ impl BoardDrawBuffer {
             invedge = invedge.reverse();
         }
 
-        return format!(
+        format!(
             "{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}",
             s0, start, inner, edge, invinner, invedge, inner, edge, invinner, 
             invedge, inner, edge, invinner, invedge, inner, edge, invinner, end, e0
-        );
+        )
     }
 }
 
@@ -2,7 +2,7 @@
 use crate::board::*;
 use bitintr::{Popcnt, Tzcnt};
 use std::fmt::Debug;
-use colored::{Colorize, ColoredString};
+use colored::Colorize;
 
 pub const RIGHT_COL: u64        = 0x8080808080808080;
 pub const LEFT_COL: u64         = 0x0101010101010101;
@@ -14,6 +14,7 @@ pub const FULL: u64             = 0xFFFFFFFFFFFFFFFF;
 pub const EMPTY: u64            = 0x0000000000000000;
 pub const BLACK_PAWN_HOME: u64  = 0x000000000000FF00; // Second-to-top row
 pub const WHITE_PAWN_HOME: u64  = 0x00FF000000000000; // Second-to-bottom row
+pub const LIGHT_SQUARES: u64    = 0xAA55AA55AA55AA55; // a8 and h1 are light squares
 
 pub const WHITE_KING_SIDE_CASTLE_TARGET: u64   = 1u64 << mailbox::WHITE_KING_SIDE_CASTLE_TARGET;
 pub const WHITE_QUEEN_SIDE_CASTLE_TARGET: u64  = 1u64 << mailbox::WHITE_QUEEN_SIDE_CASTLE_TARGET;
@@ -31,9 +32,10 @@ pub const BLACK_QUEEN_SIDE_CASTLE_OCCUPANCY: u64    = 0x000000000000000E;
 pub const BLACK_KING_SIDE_CASTLE_CHECK: u64         = 0x0000000000000070;
 pub const BLACK_QUEEN_SIDE_CASTLE_CHECK: u64        = 0x000000000000001C;
 
+#[derive(Clone, PartialEq, Eq)]
 pub struct BitBoard {
-    pub color: [u64; 2],
-    pub piece: [[u64; 6]; 2],
+    pub(crate) color: [u64; 2],
+    pub(crate) piece: [[u64; 6]; 2],
     pub occupancy: u64
 }
 
@@ -45,6 +47,16 @@ impl BitBoard {
             occupancy: 0
         }
     }
+
+    /// Gets the squares of the pieces of a type and color.
+    pub fn pieces(&self, ptype: PieceType, color: Color) -> u64 {
+        return self.piece[color as usize][ptype as usize];
+    }
+
+    /// Gets the squares of all the pieces of a color.
+    pub fn color_pieces(&self, color: Color) -> u64 {
+        return self.color[color as usize];
+    }
 }
 
 impl Debug for BitBoard {
@@ -69,33 +81,33 @@ impl Debug for BitBoard {
         for i in 0..7 as usize {
             write!(f, "{: <17}", headers[i])?;
         }
-        writeln!(f, "")?;
+        writeln!(f)?;
         for i in 0..7 as usize { write!(f, "---------------  ")?; }
-        writeln!(f, "")?;
+        writeln!(f)?;
         for j in 0..8 as usize {
             write!(f, "{} ", color[WHITE][j])?;
             for i in 0..6 as usize {
                 write!(f, "{} ", wpieces[i][j])?;
             }
-            writeln!(f, "")?;
+            writeln!(f)?;
         }
-        write!(f, "\n")?;
+        writeln!(f)?;
 
         // Print black pieces.
         for i in 0..7 as usize {
             write!(f, "{: <17}", headers[i])?;
         }
-        writeln!(f, "")?;
+        writeln!(f)?;
         for i in 0..7 as usize { write!(f, "---------------  ")?; }
-        writeln!(f, "")?;
+        writeln!(f)?;
         for j in 0..8 as usize {
             write!(f, "{} ", color[BLACK][j])?;
             for i in 0..6 as usize {
                 write!(f, "{} ", bpieces[i][j])?;
             }
-            writeln!(f, "")?;
+            writeln!(f)?;
         }
-        write!(f, "\n")?;
+        writeln!(f)?;
 
         // Print occupancy
         writeln!(f, "OCC")?;
@@ -103,7 +115,7 @@ impl Debug for BitBoard {
         for j in 0..8 as usize {
             writeln!(f, "{}", occupancy[j])?;
         }
-        write!(f, "\n")?;
+        writeln!(f)?;
 
         return Ok(());
     }
@@ -156,9 +168,9 @@ pub fn print(mut bb: u64) {
             } else {
                 format!("{}", bb & 1).bold()
             });
-            bb = bb >> 1;
+            bb >>= 1;
         }
-        println!("");
+        println!();
     }
 }
 
@@ -171,9 +183,9 @@ pub fn print_debug(mut bb: u64, mut actual: u64) {
             } else {
                 format!("{}", bb & 1).bold().red()
             });
-            bb = bb >> 1;
-            actual = actual >> 1;
+            bb >>= 1;
+            actual >>= 1;
         }
-        println!("");
+        println!();
     }
 }
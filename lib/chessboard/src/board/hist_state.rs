@@ -1,70 +1,77 @@
 
 use crate::board::*;
 
-pub const INIT_BOARD_STATE: u16 = 0;
-pub const ENP_COL: u16          = 0b11100000;
-pub const PID_COL: u16          = 0b11100000;
-pub const ENP_AVAILABLE: u16    = 0b10000;
-pub const ENP_ALL: u16          = 0b11110000;
-pub const HALFMOVE_CLOCK: u16   = 0b11111100000000;
-pub const HALFMOVE_FIFTY: u16   = 50 << 8;
+pub const INIT_BOARD_STATE: u32 = 0;
+pub const ENP_COL: u32          = 0b11100000;
+pub const PID_COL: u32          = 0b11100000;
+pub const ENP_AVAILABLE: u32    = 0b10000;
+pub const ENP_ALL: u32          = 0b11110000;
+pub const CASTLE_RIGHTS: u32    = 0b1111;
+pub const HALFMOVE_CLOCK: u32   = 0xFFFF00;
+pub const HALFMOVE_FIFTY: u32   = 100 << 8;
 
 /// Represents the parts of a state of a board that are not captured when making and unmaking
 /// moves. The bit ordering of the raw data is as follows.
 ///
-/// Bits 13 - 8 : HALFMOVE_CLOCK
+/// Bits 23 - 8 : HALFMOVE_CLOCK
 /// Bits  7 - 5 : ENP_COLUMN | CAPTURED_PIECE_ID
 /// Bit       4 : ENP_AVAILABILITY
 /// Bits  3 - 0 : CASTLE_RIGHTS
 #[derive(Clone)]
 pub struct HistState {
-    data: u16
+    data: u32
 }
 
 impl HistState {
     /// Returns true if the player still has the right to king side castle.
-    pub fn has_ksc_right(&self, turn: u8) -> bool {
+    pub fn has_ksc_right(&self, turn: Color) -> bool {
         return self.data & (0b1000 >> (turn as i16 * 2)) != 0;
     }
 
     /// Returns true if the player still has the right to queen side castle.
-    pub fn has_qsc_right(&self, turn: u8) -> bool {
+    pub fn has_qsc_right(&self, turn: Color) -> bool {
         return self.data & (0b100 >> (turn as i16 * 2)) != 0;
     }
 
 
     /// Removes the right for a player to queen side castle.
-    pub fn remove_ksc_right(&mut self, turn: u8) {
-        self.data &= !0b1000 >> (turn as i16 * 2);
+    pub fn remove_ksc_right(&mut self, turn: Color) {
+        self.data &= !(0b1000 >> (turn as i16 * 2));
     }
 
     /// Removes the right for a player to queen side castle.
-    pub fn remove_qsc_right(&mut self, turn: u8) {
-        self.data &= !0b100 >> (turn as i16 * 2);
+    pub fn remove_qsc_right(&mut self, turn: Color) {
+        self.data &= !(0b100 >> (turn as i16 * 2));
     }
 
     /// Removes all rights for a player to castle.
-    pub fn remove_castle_rights(&mut self, turn: u8) {
-        self.data &= !0b1100 >> (turn as i16 * 2);
+    pub fn remove_castle_rights(&mut self, turn: Color) {
+        self.data &= !(0b1100 >> (turn as i16 * 2));
     }
 
 
     /// Removes the right for a player to queen side castle.
-    pub fn add_ksc_right(&mut self, turn: u8) {
+    pub fn add_ksc_right(&mut self, turn: Color) {
         self.data |= 0b1000 >> (turn as i16 * 2);
     }
 
     /// Removes the right for a player to queen side castle.
-    pub fn add_qsc_right(&mut self, turn: u8) {
+    pub fn add_qsc_right(&mut self, turn: Color) {
         self.data |= 0b100 >> (turn as i16 * 2);
     }
 
     /// Removes all rights for a player to castle.
-    pub fn add_castle_rights(&mut self, turn: u8) {
+    pub fn add_castle_rights(&mut self, turn: Color) {
         self.data |= 0b1100 >> (turn as i16 * 2);
     }
 
 
+    /// Gets all four castle rights as they are stored in the low bits of the state.
+    pub fn get_castle_rights(&self) -> u8 {
+        return (self.data & CASTLE_RIGHTS) as u8;
+    }
+
+
     /// Checks the availiability of an enpassant.
     pub fn enp_avaliable(&self) -> bool {
         return self.data & ENP_AVAILABLE != 0;
@@ -72,18 +79,18 @@ impl HistState {
 
     /// Gets the column of an enpassant.
     pub fn get_enp_col(&self) -> u8 {
-        return (self.data & ENP_COL >> 5) as u8;
+        return ((self.data & ENP_COL) >> 5) as u8;
     }
 
-    /// Gets the piece type of the captured piece as defined in bitboard.
-    pub fn get_captured_piece(&self) -> u8 {
-        return (self.data & ENP_COL >> 5) as u8;
+    /// Gets the type of the piece captured by the move, if it captured one.
+    pub fn get_captured_piece(&self) -> Option<PieceType> {
+        return PieceType::from_index(((self.data & PID_COL) >> 5) as u8);
     }
 
 
     /// Sets up this move state to hold an enpassant square.
     pub fn set_enp(&mut self, enp_col: u8) {
-        self.data = (self.data & !ENP_COL) | ((enp_col << 5) as u16);
+        self.data = (self.data & !ENP_COL) | ((enp_col << 5) as u32);
         self.data |= ENP_AVAILABLE;
     }
 
@@ -92,16 +99,27 @@ impl HistState {
         self.data &= !ENP_ALL;
     }
 
-    /// Sets up this move state to hold a captured piece.
-    pub fn set_captured_piece(&mut self, enp_col: u8) {
-        self.data = (self.data & !ENP_COL) | ((enp_col << 5) as u16);
-        self.data |= ENP_AVAILABLE;
+    /// Sets up this move state to hold a captured piece, or None if the move didn't capture.
+    pub fn set_captured_piece(&mut self, ptype: Option<PieceType>) {
+        let ptype: u8 = ptype.map_or(EMPTY as u8, |ptype| ptype as u8);
+        self.data = (self.data & !ENP_ALL) | ((ptype << 5) as u32);
     }
 
 
-    /// Returns true if the 50-move rule has been met.
+    /// Returns true if the 50-move rule has been met, i.e. 100 halfmoves have been played since
+    /// the last capture or pawn move.
     pub fn halfmove_clock_done(&self) -> bool {
-        return (self.data & HALFMOVE_CLOCK) == HALFMOVE_FIFTY;
+        return (self.data & HALFMOVE_CLOCK) >= HALFMOVE_FIFTY;
+    }
+
+    /// Gets the number of halfmoves since the last capture or pawn move.
+    pub fn get_halfmove_clock(&self) -> u16 {
+        return ((self.data & HALFMOVE_CLOCK) >> 8) as u16;
+    }
+
+    /// Sets the halfmove clock to an arbitrary value (used when loading positions).
+    pub fn set_halfmove_clock(&mut self, clock: u16) {
+        self.data = (self.data & !HALFMOVE_CLOCK) | ((clock as u32) << 8);
     }
 
     /// Resets the halfmove clock.
@@ -109,38 +127,40 @@ impl HistState {
         self.data &= !HALFMOVE_CLOCK;
     }
 
-    /// Increments the halfmove clock.
+    /// Increments the halfmove clock. The clock saturates rather than wrapping, by then the game
+    /// can already be claimed as a draw.
     pub fn increment_halfmove_clock(&mut self) {
-        self.data += 1u16 << 8;
+        if self.data & HALFMOVE_CLOCK != HALFMOVE_CLOCK {
+            self.data += 1u32 << 8;
+        }
     }
     
     /// Decays castle rights after a move.
-    pub fn decay_castle_rights(&mut self, turn: u8, to: u8, from: u8) {
-        let enemy_color: u8 = (!(turn as usize == WHITE)) as u8;
-
-        // Remove castling rights caused by moving a king or rook.
-        if from == mailbox::WHITE_KING_START || from == mailbox::BLACK_KING_START {
-            self.remove_castle_rights(turn);
-        } else if from == mailbox::WHITE_KING_SIDE_ROOK_START
-            || from == mailbox::BLACK_KING_SIDE_ROOK_START {
-            self.remove_ksc_right(turn);
-        } else if from == mailbox::WHITE_QUEEN_SIDE_ROOK_START
-            || from == mailbox::BLACK_QUEEN_SIDE_ROOK_START {
-            self.remove_qsc_right(turn);
+    ///
+    /// Rights are removed based on the squares involved rather than the player that moved, as
+    /// moving a king or rook off of its starting square and capturing a rook on its starting
+    /// square both invalidate the corresponding right.
+    pub fn decay_castle_rights(&mut self, to: u8, from: u8) {
+        for sq in [ to, from ] {
+            match sq {
+                mailbox::WHITE_KING_START => self.remove_castle_rights(Color::White),
+                mailbox::WHITE_KING_SIDE_ROOK_START => self.remove_ksc_right(Color::White),
+                mailbox::WHITE_QUEEN_SIDE_ROOK_START => self.remove_qsc_right(Color::White),
+                mailbox::BLACK_KING_START => self.remove_castle_rights(Color::Black),
+                mailbox::BLACK_KING_SIDE_ROOK_START => self.remove_ksc_right(Color::Black),
+                mailbox::BLACK_QUEEN_SIDE_ROOK_START => self.remove_qsc_right(Color::Black),
+                _ => ()
+            }
         }
+    }
 
-        // Remove castling rights caused by taking an enemy rook.
-        if to == mailbox::WHITE_KING_SIDE_ROOK_START
-            || to == mailbox::BLACK_KING_SIDE_ROOK_START {
-            self.remove_ksc_right(enemy_color);
-        } else if to == mailbox::WHITE_KING_SIDE_ROOK_START
-            || to == mailbox::BLACK_KING_SIDE_ROOK_START {
-            self.remove_qsc_right(enemy_color);
-        }
-        
+    /// Returns the part of the state that describes the position (castle rights, enpassant and
+    /// halfmove clock). The captured piece only matters for unmaking moves and is masked off.
+    pub fn position_data(&self) -> u32 {
+        return if self.enp_avaliable() { self.data } else { self.data & !ENP_ALL };
     }
 
-    pub fn from_data(data: u16) -> HistState {
+    pub fn from_data(data: u32) -> HistState {
         return HistState {
             data
         }
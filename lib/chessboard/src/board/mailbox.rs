@@ -1,5 +1,6 @@
 
 use crate::board::EMPTY;
+use crate::types::*;
 
 pub const WHITE_KING_START: u8                 = 60;
 pub const WHITE_KING_SIDE_ROOK_START: u8       = 63;
@@ -20,26 +21,34 @@ pub const BLACK_QUEEN_SIDE_ROOK_TARGET: u8     = 3;
 pub const WHITE_MIN_ENPASSANT_TARGET: u8 = 40;
 pub const BLACK_MIN_ENPASSANT_TARGET: u8 = 16;
 
+#[derive(Clone, PartialEq, Eq)]
 pub struct Mailbox {
-    pub data: [u8; 64]
+    data: [Option<PieceType>; 64]
 }
 
 impl Mailbox {
-    /// Gets the PID given a row and a column.
-    /// The index of the square is relative to the top left corner seen from white's perspective.
-    pub fn at(&self, row: u8, col: u8) -> u8 {
-        return self.at_sq(row * 8 + col);
+    /// Gets the type of the piece on a square, if there is one.
+    pub fn get(&self, sq: Square) -> Option<PieceType> {
+        return self.data[sq.index() as usize];
     }
 
-    /// Gets the PID given the number of the square.
-    /// The index of the square is relative to the top left corner seen from white's perspective.
-    pub fn at_sq(&self, sq: u8) -> u8 {
-        return self.data[sq as usize];
+    /// Gets the raw piece type given the number of the square, or board::EMPTY if the square is
+    /// empty. The index of the square is relative to the top left corner seen from white's
+    /// perspective.
+    pub(crate) fn at_sq(&self, sq: u8) -> u8 {
+        return match self.data[sq as usize] {
+            Some(ptype) => ptype as u8,
+            None => EMPTY as u8
+        };
+    }
+
+    pub(crate) fn set(&mut self, sq: u8, ptype: Option<PieceType>) {
+        self.data[sq as usize] = ptype;
     }
 
     pub fn new_empty() -> Self {
         Mailbox {
-            data: [EMPTY as u8; 64]
+            data: [None; 64]
         }
     }
 }
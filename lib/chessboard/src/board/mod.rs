@@ -1,61 +1,117 @@
 
 #![allow(unused_variables, dead_code)]
 
+use crate::eval;
+use crate::generator::*;
 use crate::moves::*;
-use std::num::Wrapping;
+use crate::pgn;
+pub use crate::types::*;
 use std::fmt;
 
 pub mod bitboard;
 pub mod mailbox;
 pub mod hist_state;
+pub mod zobrist;
 
-pub const BLACK: usize = 0;
-pub const WHITE: usize = 1;
-
-pub const PAWN: usize   = 0;
-pub const KNIGHT: usize = 1;
-pub const BISHOP: usize = 2;
-pub const ROOK: usize   = 3;
-pub const QUEEN: usize  = 4;
-pub const KING: usize   = 5;
-pub const EMPTY: usize  = 6;
-
-pub const PID_EMPTY: u8         = 0b0000;
-
-pub const PID_WHITE_PAWN: u8    = 0b0001;
-pub const PID_WHITE_KNIGHT: u8  = 0b0010;
-pub const PID_WHITE_BISHOP: u8  = 0b0011;
-pub const PID_WHITE_ROOK: u8    = 0b0100;
-pub const PID_WHITE_QUEEN: u8   = 0b0101;
-pub const PID_WHITE_KING: u8    = 0b0110;
-
-pub const PID_BLACK_PAWN: u8    = 0b1001;
-pub const PID_BLACK_KNIGHT: u8  = 0b1010;
-pub const PID_BLACK_BISHOP: u8  = 0b1011;
-pub const PID_BLACK_ROOK: u8    = 0b1100;
-pub const PID_BLACK_QUEEN: u8   = 0b1101;
-pub const PID_BLACK_KING: u8    = 0b1110;
+#[cfg(test)]
+mod tests;
 
+pub(crate) const BLACK: usize = 0;
+pub(crate) const WHITE: usize = 1;
+
+pub(crate) const PAWN: usize   = 0;
+pub(crate) const KNIGHT: usize = 1;
+pub(crate) const BISHOP: usize = 2;
+pub(crate) const ROOK: usize   = 3;
+pub(crate) const QUEEN: usize  = 4;
+pub(crate) const KING: usize   = 5;
+pub(crate) const EMPTY: usize  = 6;
+
+pub const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+pub(crate) const PID_EMPTY: u8         = 0b0000;
+
+pub(crate) const PID_WHITE_PAWN: u8    = 0b0001;
+pub(crate) const PID_WHITE_KNIGHT: u8  = 0b0010;
+pub(crate) const PID_WHITE_BISHOP: u8  = 0b0011;
+pub(crate) const PID_WHITE_ROOK: u8    = 0b0100;
+pub(crate) const PID_WHITE_QUEEN: u8   = 0b0101;
+pub(crate) const PID_WHITE_KING: u8    = 0b0110;
+
+pub(crate) const PID_BLACK_PAWN: u8    = 0b1001;
+pub(crate) const PID_BLACK_KNIGHT: u8  = 0b1010;
+pub(crate) const PID_BLACK_BISHOP: u8  = 0b1011;
+pub(crate) const PID_BLACK_ROOK: u8    = 0b1100;
+pub(crate) const PID_BLACK_QUEEN: u8   = 0b1101;
+pub(crate) const PID_BLACK_KING: u8    = 0b1110;
+
+#[derive(Clone)]
 pub struct HistoryElement {
     pub last_move: Move,
-    pub new_state: hist_state::HistState
+    pub new_state: hist_state::HistState,
+    /// Zobrist hash of the position after the move.
+    pub hash: u64,
+    /// Plies since the last null move, or since the board was set up if there wasn't one.
+    pub plies_since_null: u16
 }
 
+#[derive(Clone)]
 pub struct Board {
     pub bitboard: bitboard::BitBoard,
     pub mailbox: mailbox::Mailbox,
     pub history: BoardHistory,
-    pub turn: u8,
-    pub fullmv_num: u32
+    pub turn: Color,
+    pub fullmv_num: u32,
+    /// Zobrist hash of only the pieces on the board, updated whenever a piece is written or
+    /// deleted. The full hash of each position is kept in the history.
+    pub piece_hash: u64,
+    /// Zobrist hash of only the pawns, used to cache the evaluation of the pawn structure.
+    pub pawn_hash: u64,
+    /// Material and piece-square sums for the evaluation, updated along with the piece hash.
+    pub psqt: eval::Accumulator
+}
+
+impl PartialEq for Board {
+    /// Two boards are equal if they describe the same position, i.e. if they would produce the
+    /// same fen string. The moves that were played to reach the position are not compared.
+    fn eq(&self, other: &Self) -> bool {
+        return self.bitboard == other.bitboard
+            && self.mailbox == other.mailbox
+            && self.turn == other.turn
+            && self.fullmv_num == other.fullmv_num
+            && self.state().position_data() == other.state().position_data();
+    }
+}
+
+impl fmt::Debug for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Board({})", self.to_fen())
+    }
 }
 
+#[derive(Clone)]
 pub struct BoardHistory {
     pub data: Vec<HistoryElement>
 }
 
 #[derive(Clone, Debug)]
 pub enum FenError {
-    FenMalformedError(String)
+    FenMalformedError(String),
+    FenPlacementError(String),
+    FenTurnError(String),
+    FenRightsError(String),
+    FenEnpassantError(String),
+    FenHalfmoveError(String),
+    FenFullmoveError(String),
+}
+
+/// Returned when trying to make a move that can't be played.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IllegalMove {
+    /// The flags of the move don't name a kind of move.
+    IllegalFlagsError(Move),
+    /// The move is well formed, but it isn't legal in the position.
+    IllegalMoveError(Move),
 }
 
 #[derive(Clone, Debug)]
@@ -63,6 +119,7 @@ pub enum UciError<'a> {
     UciMalformedError(&'a str),
     UciInvalidMoveError(&'a str),
     UciIllegalMoveError(Move),
+    UciFenError(FenError),
 }
 
 #[derive(Clone, Debug)]
@@ -70,6 +127,24 @@ pub enum PgnError<'a> {
     PgnMalformedError(&'a str),
     PgnInvalidMoveError(&'a str),
     PgnIllegalMoveError(Move),
+    PgnFenError(FenError),
+    /// Wraps another error with the line and column (both starting at 1) where it happened.
+    PgnLocatedError(usize, usize, Box<PgnError<'a>>),
+}
+
+impl<'a> PgnError<'a> {
+    /// Attaches a line and column to the error.
+    pub fn at(self, line: usize, col: usize) -> PgnError<'a> {
+        return PgnError::PgnLocatedError(line, col, Box::new(self));
+    }
+
+    /// Gets the line and column of the error if it is known.
+    pub fn location(&self) -> Option<(usize, usize)> {
+        return match self {
+            PgnError::PgnLocatedError(line, col, _) => Some((*line, *col)),
+            _ => None
+        };
+    }
 }
 
 impl fmt::Display for FenError {
@@ -77,6 +152,37 @@ impl fmt::Display for FenError {
         match self {
             FenError::FenMalformedError(msg) => {
                 write!(f, "invalid fen string provided ({})", msg)
+            },
+            FenError::FenPlacementError(msg) => {
+                write!(f, "invalid piece placement in fen string ({})", msg)
+            },
+            FenError::FenTurnError(msg) => {
+                write!(f, "invalid side to move in fen string ({})", msg)
+            },
+            FenError::FenRightsError(msg) => {
+                write!(f, "invalid castling rights in fen string ({})", msg)
+            },
+            FenError::FenEnpassantError(msg) => {
+                write!(f, "invalid enpassant square in fen string ({})", msg)
+            },
+            FenError::FenHalfmoveError(msg) => {
+                write!(f, "invalid halfmove clock in fen string ({})", msg)
+            },
+            FenError::FenFullmoveError(msg) => {
+                write!(f, "invalid fullmove number in fen string ({})", msg)
+            }
+        }
+    }
+}
+
+impl fmt::Display for IllegalMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IllegalMove::IllegalFlagsError(mv) => {
+                write!(f, "malformed move flags: {:#06x}", mv.get_flags())
+            },
+            IllegalMove::IllegalMoveError(mv) => {
+                write!(f, "illegal move: {}", mv.to_long_algbr())
             }
         }
     }
@@ -93,6 +199,9 @@ impl fmt::Display for UciError<'_> {
             },
             UciError::UciIllegalMoveError(mv) => {
                 write!(f, "illegal move: {} when parsing uci string", mv.to_long_algbr())
+            },
+            UciError::UciFenError(err) => {
+                write!(f, "invalid position in uci string: {}", err)
             }
         }
     }
@@ -109,6 +218,12 @@ impl fmt::Display for PgnError<'_> {
             }
             PgnError::PgnIllegalMoveError(mv) => {
                 write!(f, "illegal move: {} when parsing pgn string", mv.to_long_algbr())
+            },
+            PgnError::PgnFenError(err) => {
+                write!(f, "invalid starting position in pgn string: {}", err)
+            },
+            PgnError::PgnLocatedError(line, col, err) => {
+                write!(f, "line {}, column {}: {}", line, col, err)
             }
         }
     }
@@ -122,13 +237,16 @@ impl Board {
             history: BoardHistory {
                 data: vec![]
             },
-            turn: WHITE as u8,
-            fullmv_num: 0
+            turn: Color::White,
+            fullmv_num: 0,
+            piece_hash: 0,
+            pawn_hash: 0,
+            psqt: eval::Accumulator::new()
         }
     }
 
     pub fn from_fen(fen: &str) -> Result<Self, FenError> {
-        let mut fen_split = fen.split(' ');
+        let mut fen_split = fen.split_whitespace();
 
         // Verify that our string has all of the necessary components.
         const MSG_TOO_SHORT: &str = "fen too short";
@@ -136,38 +254,160 @@ impl Board {
         let main: &str = fen_split.next().ok_or(malformed_error.clone())?;
         let turn: &str = fen_split.next().ok_or(malformed_error.clone())?;
         let rights: &str = fen_split.next().ok_or(malformed_error.clone())?;
-        let enp_col: &str = fen_split.next().ok_or(malformed_error.clone())?;
+        let enp_sq: &str = fen_split.next().ok_or(malformed_error.clone())?;
         let hlfmv: &str = fen_split.next().ok_or(malformed_error.clone())?;
         let fullmv: &str = fen_split.next().ok_or(malformed_error.clone())?;
-
-        if turn.len() != 1 { return Err(FenError::FenMalformedError("malformed fen turn".into())); }
-        if rights.len() < 1 || rights.len() > 4 {
-            return Err(FenError::FenMalformedError("malformed fen rights".into()));
-        }
-        if enp_col.len() != 1 {
-            return Err(FenError::FenMalformedError("malformed fen enp_col".into()));
+        if fen_split.next().is_some() {
+            return Err(FenError::FenMalformedError("fen too long".into()));
         }
 
         // Parse the main part of the fen string.
-        let new_board: Self = Self::from_fen_main(main)?;
-        new_board.set_turn_from_fen(turn);
-        new_board.set_rights_from_fen(rights);
-        
+        let mut new_board: Self = Self::from_fen_main(main)?;
+        new_board.set_turn_from_fen(turn)?;
+
+        // Everything else is stored in the root element of the history.
+        let mut state: hist_state::HistState =
+            hist_state::HistState::from_data(hist_state::INIT_BOARD_STATE);
+        new_board.set_rights_from_fen(&mut state, rights)?;
+        new_board.set_enp_from_fen(&mut state, enp_sq)?;
+        Self::set_halfmove_clock_from_fen(&mut state, hlfmv)?;
+        new_board.set_fullmove_num_from_fen(fullmv)?;
+
+        new_board.history.data.push(HistoryElement {
+            last_move: Move::from_data(INVALID_MOVE),
+            new_state: state,
+            hash: 0,
+            plies_since_null: 0
+        });
+        new_board.history.data.last_mut().unwrap().hash = new_board.compute_hash();
+
         return Ok(new_board);
     }
 
     fn set_turn_from_fen(&mut self, turn: &str) -> Result<(), FenError> {
-        match turn.chars().nth(0) {
-            Some('w') => self.turn = WHITE as u8,
-            Some('b') => self.turn = BLACK as u8,
-            None => return Err(FenError::FenMalformedError("empty string in fen turn".into())),
-            _ => return Err(FenError::FenMalformedError("invalid character in fen turn".into())),
+        match turn {
+            "w" => self.turn = Color::White,
+            "b" => self.turn = Color::Black,
+            "" => return Err(FenError::FenTurnError("empty string in fen turn".into())),
+            _ => return Err(FenError::FenTurnError(format!("unexpected turn {}", turn))),
         }
         return Ok(());
     }
-    
-    fn set_rights_from_fen(&mut self, rights: &str) -> Result<(), FenError> {
-        return 
+
+    fn set_rights_from_fen(
+        &self,
+        state: &mut hist_state::HistState,
+        rights: &str
+    ) -> Result<(), FenError> {
+        if rights == "-" {
+            return Ok(());
+        }
+        if rights.is_empty() || rights.len() > 4 {
+            return Err(FenError::FenRightsError(format!("unexpected rights {}", rights)));
+        }
+
+        for c in rights.chars() {
+            let (color, is_king_side): (Color, bool) = match c {
+                'K' => (Color::White, true),
+                'Q' => (Color::White, false),
+                'k' => (Color::Black, true),
+                'q' => (Color::Black, false),
+                a => {
+                    let msg: String = format!("unexpected character {}", a);
+                    return Err(FenError::FenRightsError(msg));
+                }
+            };
+
+            let has_right: bool = if is_king_side {
+                state.has_ksc_right(color)
+            } else {
+                state.has_qsc_right(color)
+            };
+            if has_right {
+                let msg: String = format!("duplicate right {}", c);
+                return Err(FenError::FenRightsError(msg));
+            }
+
+            // A castling right is meaningless if the king and rook have left their squares.
+            let (king_sq, rook_sq): (u8, u8) = match c {
+                'K' => (mailbox::WHITE_KING_START, mailbox::WHITE_KING_SIDE_ROOK_START),
+                'Q' => (mailbox::WHITE_KING_START, mailbox::WHITE_QUEEN_SIDE_ROOK_START),
+                'k' => (mailbox::BLACK_KING_START, mailbox::BLACK_KING_SIDE_ROOK_START),
+                _ => (mailbox::BLACK_KING_START, mailbox::BLACK_QUEEN_SIDE_ROOK_START),
+            };
+            let pieces: &[u64; 6] = &self.bitboard.piece[color as usize];
+            if pieces[KING] & (1u64 << king_sq) == 0 || pieces[ROOK] & (1u64 << rook_sq) == 0 {
+                let msg: String = format!("right {} without king and rook in place", c);
+                return Err(FenError::FenRightsError(msg));
+            }
+
+            if is_king_side {
+                state.add_ksc_right(color);
+            } else {
+                state.add_qsc_right(color);
+            }
+        }
+
+        return Ok(());
+    }
+
+    fn set_enp_from_fen(
+        &self,
+        state: &mut hist_state::HistState,
+        enp_sq: &str
+    ) -> Result<(), FenError> {
+        if enp_sq == "-" {
+            return Ok(());
+        }
+
+        let chars: Vec<char> = enp_sq.chars().collect();
+        if chars.len() != 2 || !('a'..='h').contains(&chars[0]) {
+            return Err(FenError::FenEnpassantError(format!("unexpected square {}", enp_sq)));
+        }
+
+        // The enpassant square is always behind the pawn that was just pushed, so it must be on
+        // the third rank from the perspective of the player that pushed it.
+        let expected_rank: char = if self.turn as usize == WHITE { '6' } else { '3' };
+        if chars[1] != expected_rank {
+            let msg: String = format!("square {} is not on rank {}", enp_sq, expected_rank);
+            return Err(FenError::FenEnpassantError(msg));
+        }
+
+        let col: u8 = chars[0] as u8 - b'a';
+        let row: u8 = b'8' - chars[1] as u8;
+        let direction: i8 = if self.turn as usize == WHITE { 1 } else { -1 };
+        let pawn_sq: u8 = ((row * 8 + col) as i8 + 8 * direction) as u8;
+        let enemy: usize = self.enemy_color() as usize;
+        if self.bitboard.piece[enemy][PAWN] & (1u64 << pawn_sq) == 0 {
+            let msg: String = format!("no pawn in front of square {}", enp_sq);
+            return Err(FenError::FenEnpassantError(msg));
+        }
+
+        state.set_enp(col);
+        return Ok(());
+    }
+
+    fn set_halfmove_clock_from_fen(
+        state: &mut hist_state::HistState,
+        hlfmv: &str
+    ) -> Result<(), FenError> {
+        let clock: u32 = hlfmv.parse().map_err(|_| {
+            FenError::FenHalfmoveError(format!("unexpected halfmove clock {}", hlfmv))
+        })?;
+        // A clock that doesn't fit is capped, the game could be claimed as a draw long before.
+        state.set_halfmove_clock(clock.min(u16::MAX as u32) as u16);
+        return Ok(());
+    }
+
+    fn set_fullmove_num_from_fen(&mut self, fullmv: &str) -> Result<(), FenError> {
+        let num: u32 = fullmv.parse().map_err(|_| {
+            FenError::FenFullmoveError(format!("unexpected fullmove number {}", fullmv))
+        })?;
+        if num == 0 {
+            return Err(FenError::FenFullmoveError("fullmove number must start at 1".into()));
+        }
+        self.fullmv_num = num;
+        return Ok(());
     }
 
     fn from_fen_main(fen_main: &str) -> Result<Self, FenError> {
@@ -176,98 +416,272 @@ impl Board {
 
         let mut current_row: u8 = 0;
         for c in fen_main.chars() {
-            match c {
+            // Every square we write to must be on the row that we are currently parsing.
+            if c != '/' && sq >= current_row * 8 + 8 {
+                return Err(FenError::FenPlacementError(
+                        "expected '/' before wrap around".to_string()
+                ));
+            }
+
+            let ptype: usize = match c.to_ascii_lowercase() {
+                'p' => PAWN,
+                'n' => KNIGHT,
+                'b' => BISHOP,
+                'r' => ROOK,
+                'q' => QUEEN,
+                'k' => KING,
                 '1'..='8' => {
                     sq += c.to_digit(10).unwrap() as u8;
-                    if sq < current_row * 8 || sq > current_row * 8 + 8 {
-                        return Err(FenError::FenMalformedError(
+                    if sq > current_row * 8 + 8 {
+                        return Err(FenError::FenPlacementError(
                                 "expected '/' before wrap around".to_string()
                         ));
                     }
-                },
-                'p' | 'P' => {
-                    let pcolor: u8 = if c.is_uppercase() { WHITE as u8 } else { BLACK as u8 };
-                    new_board.write_piece(sq, PAWN as u8, pcolor);
-                    sq += 1;
-                },
-                'n' | 'N' => {
-                    let pcolor: u8 = if c.is_uppercase() { WHITE as u8 } else { BLACK as u8 };
-                    new_board.write_piece(sq, KNIGHT as u8, pcolor);
-                    sq += 1;
-                },
-                'b' | 'B' => {
-                    let pcolor: u8 = if c.is_uppercase() { WHITE as u8 } else { BLACK as u8 };
-                    new_board.write_piece(sq, BISHOP as u8, pcolor);
-                    sq += 1;
-                },
-                'r' | 'R' => {
-                    let pcolor: u8 = if c.is_uppercase() { WHITE as u8 } else { BLACK as u8 };
-                    new_board.write_piece(sq, ROOK as u8, pcolor);
-                    sq += 1;
-                },
-                'q' | 'Q' => {
-                    let pcolor: u8 = if c.is_uppercase() { WHITE as u8 } else { BLACK as u8 };
-                    new_board.write_piece(sq, QUEEN as u8, pcolor);
-                    sq += 1;
-                },
-                'k' | 'K' => {
-                    let pcolor: u8 = if c.is_uppercase() { WHITE as u8 } else { BLACK as u8 };
-                    new_board.write_piece(sq, KING as u8, pcolor);
-                    sq += 1;
+                    continue;
                 },
                 '/' => {
-                    if sq % 8 != 0 {
-                        return Err(FenError::FenMalformedError("unexpected '/'".to_string()))
+                    if sq != current_row * 8 + 8 || current_row == 7 {
+                        return Err(FenError::FenPlacementError("unexpected '/'".to_string()))
                     }
                     current_row += 1;
+                    continue;
                 },
-                a => {
-                    let msg: String = format!("unexpected character {}", a);
-                    return Err(FenError::FenMalformedError(msg))
+                _ => {
+                    let msg: String = format!("unexpected character {}", c);
+                    return Err(FenError::FenPlacementError(msg))
                 }
-            }
+            };
+
+            let pcolor: Color = if c.is_uppercase() { Color::White } else { Color::Black };
+            new_board.write_piece(sq, ptype as u8, pcolor);
+            sq += 1;
         }
 
         if sq < 64 {
-            return Err(FenError::FenMalformedError(
+            return Err(FenError::FenPlacementError(
                     "incomplete main fen string".to_string()
             ));
-        } else if sq > 64 {
-            return Err(FenError::FenMalformedError(
-                    "mian fen contained too many elements".to_string()
-            ));
         }
-        
+
+        // Move generation assumes that there is exactly one king of each color.
+        for color in [ WHITE, BLACK ] {
+            if bitboard::popcnt(&new_board.bitboard.piece[color][KING]) != 1 {
+                return Err(FenError::FenPlacementError(
+                        "expected exactly one king per side".to_string()
+                ));
+            }
+        }
+
         return Ok(new_board);
     }
 
-    pub fn from_pgn(pgn: &str) -> Result<Self, PgnError> {
-        todo!()
+    /// Serializes the current position into a fen string.
+    pub fn to_fen(&self) -> String {
+        let mut fen: String = String::new();
+
+        // Piece placement, starting from a8 as square 0 matches the fen ordering.
+        for row in 0..8 {
+            let mut empty: u8 = 0;
+            for col in 0..8 {
+                let piece: Piece = match self.piece_at(Square::from_raw(row * 8 + col)) {
+                    Some(piece) => piece,
+                    None => {
+                        empty += 1;
+                        continue;
+                    }
+                };
+                if empty != 0 {
+                    fen.push((b'0' + empty) as char);
+                    empty = 0;
+                }
+                fen.push(piece.to_char());
+            }
+            if empty != 0 {
+                fen.push((b'0' + empty) as char);
+            }
+            if row != 7 {
+                fen.push('/');
+            }
+        }
+
+        fen.push(' ');
+        fen.push(self.turn.to_char());
+
+        let state: &hist_state::HistState = self.state();
+        fen.push(' ');
+        let mut rights: String = String::new();
+        if state.has_ksc_right(Color::White) { rights.push('K'); }
+        if state.has_qsc_right(Color::White) { rights.push('Q'); }
+        if state.has_ksc_right(Color::Black) { rights.push('k'); }
+        if state.has_qsc_right(Color::Black) { rights.push('q'); }
+        fen.push_str(if rights.is_empty() { "-" } else { &rights });
+
+        fen.push(' ');
+        if state.enp_avaliable() {
+            fen.push((b'a' + state.get_enp_col()) as char);
+            fen.push(if self.turn as usize == WHITE { '6' } else { '3' });
+        } else {
+            fen.push('-');
+        }
+
+        fen.push_str(&format!(" {} {}", state.get_halfmove_clock(), self.fullmv_num));
+        return fen;
+    }
+
+    /// Gets the state of the current position from the top of the history.
+    pub fn state(&self) -> &hist_state::HistState {
+        return &self.history.data.last().unwrap().new_state;
+    }
+
+    /// Gets the zobrist hash of the current position.
+    pub fn hash(&self) -> u64 {
+        return self.history.data.last().unwrap().hash;
+    }
+
+    /// Computes the zobrist hash of the current position from scratch. This should always match
+    /// the hash that is maintained by make and unmake.
+    pub fn compute_hash(&self) -> u64 {
+        let keys: &zobrist::ZobristKeys = zobrist::ZobristKeys::get_instance();
+        let mut hash: u64 = 0;
+        for pcolor in [ Color::White, Color::Black ] {
+            for ptype in PieceType::ALL {
+                let mut pieces: u64 = self.bitboard.pieces(ptype, pcolor);
+                while pieces != 0 {
+                    let sq: Square = Square::from_raw(bitboard::pop_rbit(&mut pieces));
+                    hash ^= keys.piece(sq, ptype, pcolor);
+                }
+            }
+        }
+        return hash ^ self.state_hash(self.state());
     }
 
-    pub fn from_uci(uci: &str) -> Result<Self, UciError> {
-        todo!()
+    /// Computes the pawn hash from scratch. This is kept up to date in the pawn_hash field.
+    pub fn compute_pawn_hash(&self) -> u64 {
+        let keys: &zobrist::ZobristKeys = zobrist::ZobristKeys::get_instance();
+        let mut hash: u64 = 0;
+        for pcolor in [ Color::White, Color::Black ] {
+            let mut pawns: u64 = self.bitboard.pieces(PieceType::Pawn, pcolor);
+            while pawns != 0 {
+                let sq: Square = Square::from_raw(bitboard::pop_rbit(&mut pawns));
+                hash ^= keys.piece(sq, PieceType::Pawn, pcolor);
+            }
+        }
+        return hash;
+    }
+
+    /// Hashes the parts of the position that are not pieces (turn, castle rights, enpassant).
+    fn state_hash(&self, state: &hist_state::HistState) -> u64 {
+        let keys: &zobrist::ZobristKeys = zobrist::ZobristKeys::get_instance();
+        let mut hash: u64 = keys.castle(state.get_castle_rights());
+        if self.turn as usize == BLACK {
+            hash ^= keys.side();
+        }
+
+        // Only hash enpassants that a pawn could take, otherwise a double pawn push would never
+        // transpose with the same position reached by two single pushes.
+        if state.enp_avaliable() {
+            let col: u8 = state.get_enp_col();
+            let row: u8 = if self.turn as usize == WHITE { 3 } else { 4 };
+            let sq: u8 = row * 8 + col;
+            let mut neighbors: u64 = 0;
+            if col > 0 {
+                neighbors |= 1u64 << (sq - 1);
+            }
+            if col < 7 {
+                neighbors |= 1u64 << (sq + 1);
+            }
+            if self.bitboard.piece[self.turn as usize][PAWN] & neighbors != 0 {
+                hash ^= keys.enp(col);
+            }
+        }
+        return hash;
+    }
+
+    /// Returns true if the current position has occurred at least count times, including the
+    /// current occurrence. Only positions since the last capture or pawn move are compared, as
+    /// nothing before an irreversible move can repeat. Positions from before the board was set
+    /// up are unknown and never count, and neither do positions from before a null move.
+    pub fn is_repetition(&self, count: usize) -> bool {
+        let hash: u64 = self.hash();
+        let last: usize = self.history.data.len() - 1;
+        let reversible: usize = self.state().get_halfmove_clock() as usize;
+        let since_null: usize = self.history.data[last].plies_since_null as usize;
+
+        let mut seen: usize = 1;
+        let mut plies: usize = 2;
+        while seen < count && plies <= reversible && plies <= since_null {
+            if self.history.data[last - plies].hash == hash {
+                seen += 1;
+            }
+            plies += 2;
+        }
+        return seen >= count;
+    }
+
+    /// Returns true if the game can be drawn by the 50-move rule. A checkmate delivered on the
+    /// last move still takes precedence, which is left to the caller.
+    pub fn is_fifty_move_draw(&self) -> bool {
+        return self.state().halfmove_clock_done();
+    }
+
+    /// Returns true if neither side has enough material left to deliver checkmate, i.e. kings
+    /// with at most one minor piece, or kings and bishops that all stand on the same color.
+    pub fn is_insufficient_material(&self) -> bool {
+        for pcolor in [ WHITE, BLACK ] {
+            let pieces: &[u64; 6] = &self.bitboard.piece[pcolor];
+            if pieces[PAWN] | pieces[ROOK] | pieces[QUEEN] != 0 {
+                return false;
+            }
+        }
+
+        let knights: u64 = self.bitboard.piece[WHITE][KNIGHT] | self.bitboard.piece[BLACK][KNIGHT];
+        let bishops: u64 = self.bitboard.piece[WHITE][BISHOP] | self.bitboard.piece[BLACK][BISHOP];
+        if bitboard::popcnt(&(knights | bishops)) <= 1 {
+            return true;
+        }
+        return knights == 0
+            && (bishops & bitboard::LIGHT_SQUARES == 0 || bishops & !bitboard::LIGHT_SQUARES == 0);
+    }
+
+    /// Parses a single game from a pgn string. See the pgn module for the details of what is
+    /// kept from the game, the final position is available as the board of the returned game.
+    pub fn from_pgn(pgn: &str) -> Result<pgn::Game, PgnError<'_>> {
+        return pgn::Game::from_pgn(pgn);
+    }
+
+    /// Parses a position as given to the UCI position command, without the leading "position":
+    /// either "startpos" or "fen" followed by a FEN string, then optionally "moves" and the moves
+    /// played from there in long algebraic notation. The moves are kept in the history of the
+    /// board, so that repetitions are recognized.
+    pub fn from_uci(uci: &str) -> Result<Self, UciError<'_>> {
+        let tokens: Vec<&str> = uci.split_whitespace().collect();
+        let moves_at: usize = tokens.iter().position(|token| *token == "moves")
+            .unwrap_or(tokens.len());
+        let mut board: Board = match tokens[..moves_at] {
+            [ "startpos" ] => Board::from_fen(START_FEN).unwrap(),
+            [ "fen", ref fen @ .. ] if !fen.is_empty() => {
+                Board::from_fen(&fen.join(" ")).map_err(UciError::UciFenError)?
+            },
+            _ => return Err(UciError::UciMalformedError(uci))
+        };
+
+        let generator: MoveGenerator = MoveGenerator::new();
+        for algbr in tokens.iter().skip(moves_at + 1) {
+            let mut moves: MoveList = MoveList::new();
+            let state: BoardTables = generator.gen_board_tables(&board);
+            generator.gen_moves(&mut moves, &board, &state);
+            let mv: Move = Move::from_uci_algbr(algbr, &moves)?;
+            board.make(&mv);
+        }
+        return Ok(board);
     }
 
     pub fn str_rep(&self) -> Box<[[char; 8]; 8]> {
         let mut arr: Box<[[char; 8]; 8]> = Box::new([[' '; 8]; 8]);
 
-        for row in 0..8 as usize {
-            for col  in 0..8 as usize {
-                let ptype = self.type_at(row as u8, col as u8);
-                let pcolor = self.color_at(row as u8, col as u8);
-                let c = match ptype as usize {
-                    PAWN => 'p',
-                    KNIGHT => 'k',
-                    BISHOP => 'b',
-                    ROOK => 'r',
-                    QUEEN => 'q',
-                    KING => 'k',
-                    EMPTY => ' ',
-                    _ => panic!("Invalid piece type in string conversion: ({},{}:{})",
-                        row, col, ptype),
-                };
-                arr[row][col] = if pcolor as usize == WHITE { c.to_ascii_uppercase() } else { c };
+        for sq in Square::all() {
+            if let Some(piece) = self.piece_at(sq) {
+                arr[sq.row() as usize][sq.col() as usize] = piece.to_char();
             }
         }
 
@@ -277,42 +691,50 @@ impl Board {
     pub fn str_rep_utf8(&self) -> Box<[[char; 8]; 8]> {
         let mut arr: Box<[[char; 8]; 8]> = Box::new([[' '; 8]; 8]);
 
-        for row in 0..8 as usize {
-            for col  in 0..8 as usize {
-                let ptype = self.type_at(row as u8, col as u8);
-                let pcolor = self.color_at(row as u8, col as u8);
-                let c = match ptype as usize {
-                    PAWN => if pcolor as usize == WHITE { '\u{2659}' } else { '\u{265F}' }
-                    KNIGHT => if pcolor as usize == WHITE { '\u{2658}' } else { '\u{265E}' }
-                    BISHOP => if pcolor as usize == WHITE { '\u{2657}' } else { '\u{265D}' }
-                    ROOK => if pcolor as usize == WHITE { '\u{2656}' } else { '\u{265B}' }
-                    QUEEN => if pcolor as usize == WHITE { '\u{2655}' } else { '\u{265C}' }
-                    KING => if pcolor as usize == WHITE { '\u{2654}' } else { '\u{265A}' }
-                    EMPTY => ' ',
-                    _ => panic!("Invalid piece type in string conversion: ({},{}:{})",
-                        row, col, ptype),
-                };
-                arr[row][col] = c
-            }
+        for sq in Square::all() {
+            let piece: Piece = match self.piece_at(sq) {
+                Some(piece) => piece,
+                None => continue
+            };
+            let is_white: bool = piece.color == Color::White;
+            arr[sq.row() as usize][sq.col() as usize] = match piece.ptype {
+                PieceType::Pawn => if is_white { '\u{2659}' } else { '\u{265F}' }
+                PieceType::Knight => if is_white { '\u{2658}' } else { '\u{265E}' }
+                PieceType::Bishop => if is_white { '\u{2657}' } else { '\u{265D}' }
+                PieceType::Rook => if is_white { '\u{2656}' } else { '\u{265B}' }
+                PieceType::Queen => if is_white { '\u{2655}' } else { '\u{265C}' }
+                PieceType::King => if is_white { '\u{2654}' } else { '\u{265A}' }
+            };
         }
 
         return arr
     }
 
-    pub fn type_at(&self, row: u8, col: u8) -> u8 {
-        return self.mailbox.at(row, col);
+    /// Gets the piece on a square, if there is one.
+    pub fn piece_at(&self, sq: Square) -> Option<Piece> {
+        let ptype: PieceType = self.mailbox.get(sq)?;
+        return Some(Piece::new(ptype, self.color_at_sq(sq.index())));
     }
 
-    pub fn type_at_sq(&self, sq: u8) -> u8 {
-        return self.mailbox.at_sq(sq);
+    /// Gets the type of the piece on a square, if there is one.
+    pub fn type_at(&self, sq: Square) -> Option<PieceType> {
+        return self.mailbox.get(sq);
     }
 
-    pub fn color_at(&self, row: u8, col: u8) -> u8 {
-        return self.color_at_sq((row << 3) + col);
+    /// Gets the color of the piece on a square, if there is one.
+    pub fn color_at(&self, sq: Square) -> Option<Color> {
+        return self.piece_at(sq).map(|piece| piece.color);
+    }
+
+    /// Gets the raw type of the piece on a square, board::EMPTY if there is none.
+    pub(crate) fn type_at_sq(&self, sq: u8) -> u8 {
+        return self.mailbox.at_sq(sq);
     }
 
-    pub fn color_at_sq(&self, sq: u8) -> u8 {
-        return if self.bitboard.color[WHITE] & (1u64 << sq) == 0 { 0 } else { 1 }
+    /// Gets the color of the piece on an occupied square.
+    pub(crate) fn color_at_sq(&self, sq: u8) -> Color {
+        debug_assert!(self.bitboard.occupancy & (1u64 << sq) != 0);
+        return if self.bitboard.color[WHITE] & (1u64 << sq) == 0 { Color::Black } else { Color::White }
     }
 
 //    pub fn pid_at(&self, row: u8, col: u8) -> u8 {
@@ -323,37 +745,82 @@ impl Board {
 //        return self.mailbox.at_sq(sq);
 //    }
 
-    fn replace_piece(&mut self, sq: u8, ptype: u8, pcolor: u8, old_ptype: u8, old_pcolor: u8) {
-        self.mailbox.data[sq as usize] = ptype;
+    fn replace_piece(&mut self, sq: u8, ptype: u8, pcolor: Color, old_ptype: u8, old_pcolor: Color) {
+        let keys: &zobrist::ZobristKeys = zobrist::ZobristKeys::get_instance();
+        self.piece_hash ^= keys.piece_sq(sq, ptype, pcolor)
+            ^ keys.piece_sq(sq, old_ptype, old_pcolor);
+        if ptype as usize == PAWN {
+            self.pawn_hash ^= keys.piece_sq(sq, ptype, pcolor);
+        }
+        if old_ptype as usize == PAWN {
+            self.pawn_hash ^= keys.piece_sq(sq, old_ptype, old_pcolor);
+        }
+        self.psqt.remove(sq, old_ptype, old_pcolor);
+        self.psqt.add(sq, ptype, pcolor);
+        self.mailbox.set(sq, PieceType::from_index(ptype));
         self.bitboard.piece[pcolor as usize][ptype as usize] |= 1u64 << sq;
         self.bitboard.color[pcolor as usize] |= 1u64 << sq;
         self.bitboard.piece[old_pcolor as usize][old_ptype as usize] &= !(1u64 << sq);
         self.bitboard.color[old_pcolor as usize] &= !(1u64 << sq);
     }
 
-    fn write_piece(&mut self, sq: u8, ptype: u8, pcolor: u8) {
-        self.mailbox.data[sq as usize] = ptype;
+    fn write_piece(&mut self, sq: u8, ptype: u8, pcolor: Color) {
+        let key: u64 = zobrist::ZobristKeys::get_instance().piece_sq(sq, ptype, pcolor);
+        self.piece_hash ^= key;
+        if ptype as usize == PAWN {
+            self.pawn_hash ^= key;
+        }
+        self.psqt.add(sq, ptype, pcolor);
+        self.mailbox.set(sq, PieceType::from_index(ptype));
         self.bitboard.piece[pcolor as usize][ptype as usize] |= 1u64 << sq;
         self.bitboard.color[pcolor as usize] |= 1u64 << sq;
         self.bitboard.occupancy |= 1u64 << sq;
     }
 
-    fn delete_piece(&mut self, sq: u8, old_ptype: u8, old_pcolor: u8) {
-        self.mailbox.data[sq as usize] = EMPTY as u8;
+    fn delete_piece(&mut self, sq: u8, old_ptype: u8, old_pcolor: Color) {
+        let key: u64 = zobrist::ZobristKeys::get_instance().piece_sq(sq, old_ptype, old_pcolor);
+        self.piece_hash ^= key;
+        if old_ptype as usize == PAWN {
+            self.pawn_hash ^= key;
+        }
+        self.psqt.remove(sq, old_ptype, old_pcolor);
+        self.mailbox.set(sq, None);
         self.bitboard.piece[old_pcolor as usize][old_ptype as usize] &= !(1u64 << sq);
         self.bitboard.color[old_pcolor as usize] &= !(1u64 << sq);
         self.bitboard.occupancy &= !(1u64 << sq);
     }
     
     fn wipe_board(&mut self) {
-        for datum in &mut self.mailbox.data { *datum = 0; }
+        self.mailbox = mailbox::Mailbox::new_empty();
         for bb in &mut self.bitboard.piece[WHITE] { *bb = 0; }
         for bb in &mut self.bitboard.piece[BLACK] {*bb = 0; }
         self.bitboard.color[WHITE] = 0;
         self.bitboard.color[BLACK] = 0;
         self.bitboard.occupancy = 0;
+        self.piece_hash = 0;
+        self.pawn_hash = 0;
+        self.psqt = eval::Accumulator::new();
     }
 
+    /// Makes a move after checking that it is legal, leaving the board untouched if it isn't.
+    /// Use this for moves that come from outside of the move generator.
+    pub fn try_make(&mut self, mv: &Move) -> Result<(), IllegalMove> {
+        if !mv.has_valid_flags() {
+            return Err(IllegalMove::IllegalFlagsError(mv.clone()));
+        }
+
+        let generator: MoveGenerator = MoveGenerator::new();
+        let state: BoardTables = generator.gen_board_tables(self);
+        if !generator.is_legal(self, &state, mv) {
+            return Err(IllegalMove::IllegalMoveError(mv.clone()));
+        }
+
+        self.make(mv);
+        return Ok(());
+    }
+
+    /// Makes a move without checking it. The move must be legal, e.g. taken from the move
+    /// generator, otherwise the board is left in an invalid state.
     pub fn make(&mut self, mv: &Move) {
         let extra: &hist_state::HistState = &self.history.data.last().unwrap().new_state;
         let mut new_state: hist_state::HistState = extra.clone();
@@ -362,27 +829,37 @@ impl Board {
         let to: u8 = mv.get_to();
         let from: u8 = mv.get_from();
 
+        // An enpassant is only available for the move directly after a double pawn push.
+        new_state.decay_enp();
+        new_state.decay_castle_rights(to, from);
+
+        // Captures and pawn moves can't be undone, so they restart the count for the 50-move rule.
+        if mv.is_capture() || self.type_at_sq(from) as usize == PAWN {
+            new_state.reset_halfmove_clock();
+        } else {
+            new_state.increment_halfmove_clock();
+        }
+
         match flags {
             QUIET => {
                 let ptype: u8 = self.type_at_sq(from);
-                let pcolor: u8 = self.turn;
-                new_state.set_captured_piece(EMPTY as u8);
-                new_state.decay_castle_rights(self.turn, to, from);
+                let pcolor: Color = self.turn;
+                new_state.set_captured_piece(None);
                 self.write_piece(to, ptype, pcolor);
+                self.delete_piece(from, ptype, pcolor);
             },
             CAPTURE => {
                 let ptype: u8 = self.type_at_sq(from);
-                let pcolor: u8 = self.turn;
+                let pcolor: Color = self.turn;
                 let cap_ptype: u8 = self.type_at_sq(to);
-                let cap_pcolor: u8 = self.enemy_color();
-                new_state.set_captured_piece(EMPTY as u8);
-                new_state.decay_castle_rights(self.turn, to, from);
+                let cap_pcolor: Color = self.enemy_color();
+                new_state.set_captured_piece(PieceType::from_index(cap_ptype));
                 self.replace_piece(to, ptype, pcolor, cap_ptype, cap_pcolor);
                 self.delete_piece(from, ptype, pcolor);
             },
             DOUBLE_PAWN_PUSH => {
                 let ptype: u8 = PAWN as u8;
-                let pcolor: u8 = self.turn;
+                let pcolor: Color = self.turn;
                 new_state.set_enp(to & 0b111);
                 self.write_piece(to, ptype, pcolor);
                 self.delete_piece(from, ptype, pcolor);
@@ -412,7 +889,7 @@ impl Board {
                     mailbox::BLACK_KING_SIDE_ROOK_TARGET
                 };
 
-                new_state.set_captured_piece(EMPTY as u8);
+                new_state.set_captured_piece(None);
                 new_state.remove_castle_rights(self.turn);
                 self.delete_piece(king_from, KING as u8, self.turn);
                 self.write_piece(king_to, KING as u8, self.turn);
@@ -444,7 +921,7 @@ impl Board {
                     mailbox::BLACK_QUEEN_SIDE_ROOK_TARGET
                 };
 
-                new_state.set_captured_piece(EMPTY as u8);
+                new_state.set_captured_piece(None);
                 new_state.remove_castle_rights(self.turn);
                 self.delete_piece(king_from, KING as u8, self.turn);
                 self.write_piece(king_to, KING as u8, self.turn);
@@ -453,79 +930,124 @@ impl Board {
             },
             ENPASSANT => {
                 let direction: i8 = if self.turn as usize == WHITE { 1 } else { -1 };
-                new_state.set_captured_piece(PAWN as u8);
+                new_state.set_captured_piece(Some(PieceType::Pawn));
                 self.delete_piece(from, PAWN as u8, self.turn);
                 self.write_piece(to, PAWN as u8, self.turn);
                 self.delete_piece((to as i8 + 8 * direction) as u8, PAWN as u8, self.enemy_color());
             },
             KNIGHT_PROMO => {
-                new_state.set_captured_piece(EMPTY as u8);
+                new_state.set_captured_piece(None);
                 self.delete_piece(from, PAWN as u8, self.turn);
-                self.write_piece(from, KNIGHT as u8, self.turn);
+                self.write_piece(to, KNIGHT as u8, self.turn);
             },
             BISHOP_PROMO => {
-                new_state.set_captured_piece(EMPTY as u8);
+                new_state.set_captured_piece(None);
                 self.delete_piece(from, PAWN as u8, self.turn);
-                self.write_piece(from, BISHOP as u8, self.turn);
+                self.write_piece(to, BISHOP as u8, self.turn);
             },
             ROOK_PROMO => {
-                new_state.set_captured_piece(EMPTY as u8);
+                new_state.set_captured_piece(None);
                 self.delete_piece(from, PAWN as u8, self.turn);
-                self.write_piece(from, ROOK as u8, self.turn);
+                self.write_piece(to, ROOK as u8, self.turn);
             },
             QUEEN_PROMO => {
-                new_state.set_captured_piece(EMPTY as u8);
+                new_state.set_captured_piece(None);
                 self.delete_piece(from, PAWN as u8, self.turn);
-                self.write_piece(from, QUEEN as u8, self.turn);
+                self.write_piece(to, QUEEN as u8, self.turn);
             },
             KNIGHT_PROMO_CAPTURE => {
                 let cap_ptype: u8 = self.type_at_sq(to);
-                let cap_pcolor: u8 = self.enemy_color();
-                new_state.set_captured_piece(cap_ptype);
+                let cap_pcolor: Color = self.enemy_color();
+                new_state.set_captured_piece(PieceType::from_index(cap_ptype));
                 self.delete_piece(from, PAWN as u8, self.turn);
-                self.replace_piece(from, KNIGHT as u8, self.turn, cap_ptype, cap_pcolor);
+                self.replace_piece(to, KNIGHT as u8, self.turn, cap_ptype, cap_pcolor);
             },
             BISHOP_PROMO_CAPTURE => {
                 let cap_ptype: u8 = self.type_at_sq(to);
-                let cap_pcolor: u8 = self.enemy_color();
-                new_state.set_captured_piece(cap_ptype);
+                let cap_pcolor: Color = self.enemy_color();
+                new_state.set_captured_piece(PieceType::from_index(cap_ptype));
                 self.delete_piece(from, PAWN as u8, self.turn);
-                self.replace_piece(from, BISHOP as u8, self.turn, cap_ptype, cap_pcolor);
+                self.replace_piece(to, BISHOP as u8, self.turn, cap_ptype, cap_pcolor);
             },
             ROOK_PROMO_CAPTURE => {
                 let cap_ptype: u8 = self.type_at_sq(to);
-                let cap_pcolor: u8 = self.enemy_color();
-                new_state.set_captured_piece(cap_ptype);
+                let cap_pcolor: Color = self.enemy_color();
+                new_state.set_captured_piece(PieceType::from_index(cap_ptype));
                 self.delete_piece(from, PAWN as u8, self.turn);
-                self.replace_piece(from, ROOK as u8, self.turn, cap_ptype, cap_pcolor);
+                self.replace_piece(to, ROOK as u8, self.turn, cap_ptype, cap_pcolor);
             },
             QUEEN_PROMO_CAPTURE => {
                 let cap_ptype: u8 = self.type_at_sq(to);
-                let cap_pcolor: u8 = self.enemy_color();
-                new_state.set_captured_piece(cap_ptype);
+                let cap_pcolor: Color = self.enemy_color();
+                new_state.set_captured_piece(PieceType::from_index(cap_ptype));
                 self.delete_piece(from, PAWN as u8, self.turn);
-                self.replace_piece(from, QUEEN as u8, self.turn, cap_ptype, cap_pcolor);
+                self.replace_piece(to, QUEEN as u8, self.turn, cap_ptype, cap_pcolor);
             },
             _ => {
                 panic!();
             }
         }
 
-        self.turn = if self.turn == WHITE as u8 { BLACK as u8 } else { WHITE as u8 };
+        if self.turn == Color::Black {
+            self.fullmv_num += 1;
+        }
+        self.turn = if self.turn == Color::White { Color::Black } else { Color::White };
+        let hash: u64 = self.piece_hash ^ self.state_hash(&new_state);
         let ele: HistoryElement = HistoryElement {
             last_move: mv.clone(),
-            new_state
+            new_state,
+            hash,
+            plies_since_null: self.history.data.last().unwrap().plies_since_null.saturating_add(1)
         };
         self.history.data.push(ele);
+        debug_assert_eq!(self.hash(), self.compute_hash());
     }
     
-    pub fn unmake(&mut self) {
-        self.turn = if self.turn == WHITE as u8 { BLACK as u8 } else { WHITE as u8 };
+    /// Passes the turn to the other color without moving a piece, as used by null move pruning.
+    /// An enpassant can't be taken after it. The halfmove clock goes on, but no position before
+    /// the null move counts as a repetition of one after it.
+    pub fn make_null(&mut self) {
+        let mut new_state: hist_state::HistState = self.state().clone();
+        new_state.decay_enp();
+        new_state.increment_halfmove_clock();
+
+        if self.turn == Color::Black {
+            self.fullmv_num += 1;
+        }
+        self.turn = self.enemy_color();
+        let hash: u64 = self.piece_hash ^ self.state_hash(&new_state);
+        self.history.data.push(HistoryElement {
+            last_move: Move::from_data(INVALID_MOVE),
+            new_state,
+            hash,
+            plies_since_null: 0
+        });
+        debug_assert_eq!(self.hash(), self.compute_hash());
+    }
 
-        let ele = self.history.data.pop();
-        if ele.is_none() {
+    /// Takes back a null move made by make_null.
+    pub fn unmake_null(&mut self) {
+        debug_assert!(self.history.data.len() > 1);
+        debug_assert!(!self.history.data.last().unwrap().last_move.has_valid_flags());
+        self.history.data.pop();
+        self.turn = self.enemy_color();
+        if self.turn == Color::Black {
+            self.fullmv_num -= 1;
+        }
+    }
+
+    pub fn unmake(&mut self) {
+        // The root of the history holds the state of the initial position and is never undone.
+        if self.history.data.len() <= 1 {
             return;
         }
+
+        self.turn = if self.turn == Color::White { Color::Black } else { Color::White };
+        if self.turn == Color::Black {
+            self.fullmv_num -= 1;
+        }
+
+        let ele = self.history.data.pop();
         let mv: &Move = &ele.as_ref().unwrap().last_move;
         let state: &hist_state::HistState = &ele.as_ref().unwrap().new_state;
 
@@ -536,16 +1058,16 @@ impl Board {
         match flags {
             QUIET | DOUBLE_PAWN_PUSH => {
                 let ptype: u8 = self.type_at_sq(to);
-                let pcolor: u8 = self.turn;
+                let pcolor: Color = self.turn;
                 self.write_piece(from, ptype, pcolor); self.delete_piece(to, ptype, pcolor);
             },
             CAPTURE => {
-                let ptype: u8 = self.type_at_sq(from);
-                let pcolor: u8 = self.turn;
-                let cap_ptype: u8 = state.get_captured_piece();
-                let cap_pcolor: u8 = self.enemy_color();
+                let ptype: u8 = self.type_at_sq(to);
+                let pcolor: Color = self.turn;
+                let cap_ptype: u8 = state.get_captured_piece().unwrap() as u8;
+                let cap_pcolor: Color = self.enemy_color();
                 self.write_piece(from, ptype, pcolor);
-                self.replace_piece(to, ptype, pcolor, cap_ptype, cap_pcolor);
+                self.replace_piece(to, cap_ptype, cap_pcolor, ptype, pcolor);
             },
             KING_SIDE_CASTLE => {
                 let king_from: u8 = if self.turn as usize == WHITE {
@@ -611,7 +1133,7 @@ impl Board {
                 let direction: i8 = if self.turn as usize == WHITE { 1 } else { -1 };
                 self.delete_piece(to, PAWN as u8, self.turn);
                 self.write_piece(from, PAWN as u8, self.turn);
-                self.write_piece(from, PAWN as u8, self.enemy_color());
+                self.write_piece((to as i8 + 8 * direction) as u8, PAWN as u8, self.enemy_color());
             },
             KNIGHT_PROMO => {
                 self.delete_piece(to, KNIGHT as u8, self.turn);
@@ -630,37 +1152,37 @@ impl Board {
                 self.write_piece(from, PAWN as u8, self.turn);
             },
             KNIGHT_PROMO_CAPTURE => {
-                let cap_ptype: u8 = state.get_captured_piece();
-                let cap_pcolor: u8 = self.enemy_color();
-                self.replace_piece(to, KNIGHT as u8, self.turn, cap_ptype, cap_pcolor);
+                let cap_ptype: u8 = state.get_captured_piece().unwrap() as u8;
+                let cap_pcolor: Color = self.enemy_color();
+                self.replace_piece(to, cap_ptype, cap_pcolor, KNIGHT as u8, self.turn);
                 self.write_piece(from, PAWN as u8, self.turn);
             },
             BISHOP_PROMO_CAPTURE => {
-                let cap_ptype: u8 = state.get_captured_piece();
-                let cap_pcolor: u8 = self.enemy_color();
-                self.replace_piece(to, BISHOP as u8, self.turn, cap_ptype, cap_pcolor);
+                let cap_ptype: u8 = state.get_captured_piece().unwrap() as u8;
+                let cap_pcolor: Color = self.enemy_color();
+                self.replace_piece(to, cap_ptype, cap_pcolor, BISHOP as u8, self.turn);
                 self.write_piece(from, PAWN as u8, self.turn);
             },
             ROOK_PROMO_CAPTURE => {
-                let cap_ptype: u8 = state.get_captured_piece();
-                let cap_pcolor: u8 = self.enemy_color();
-                self.replace_piece(to, ROOK as u8, self.turn, cap_ptype, cap_pcolor);
+                let cap_ptype: u8 = state.get_captured_piece().unwrap() as u8;
+                let cap_pcolor: Color = self.enemy_color();
+                self.replace_piece(to, cap_ptype, cap_pcolor, ROOK as u8, self.turn);
                 self.write_piece(from, PAWN as u8, self.turn);
             },
             QUEEN_PROMO_CAPTURE => {
-                let cap_ptype: u8 = state.get_captured_piece();
-                let cap_pcolor: u8 = self.enemy_color();
-                self.replace_piece(to, QUEEN as u8, self.turn, cap_ptype, cap_pcolor);
+                let cap_ptype: u8 = state.get_captured_piece().unwrap() as u8;
+                let cap_pcolor: Color = self.enemy_color();
+                self.replace_piece(to, cap_ptype, cap_pcolor, QUEEN as u8, self.turn);
                 self.write_piece(from, PAWN as u8, self.turn);
             },
             _ => {
                 panic!()
             }
         }
+        debug_assert_eq!(self.piece_hash ^ self.state_hash(self.state()), self.hash());
     }
     
-    pub fn enemy_color(&self) -> u8 {
-        return (!(self.turn as usize == WHITE)) as u8;
+    pub fn enemy_color(&self) -> Color {
+        return self.turn.opposite();
     }
 }
-
@@ -0,0 +1,511 @@
+
+use crate::board::*;
+use crate::tests::helpers::*;
+
+#[test]
+fn test_fen_start_position() {
+    let board: Board = Board::from_fen(START_FEN).unwrap();
+    let state: &hist_state::HistState = board.state();
+
+    assert_eq!(board.turn, Color::White);
+    assert_eq!(board.fullmv_num, 1);
+    assert_eq!(board.history.data.len(), 1);
+    assert_eq!(board.bitboard.occupancy, 0xFFFF00000000FFFFu64);
+    assert_eq!(board.bitboard.color[WHITE], 0xFFFF000000000000u64);
+    assert_eq!(board.type_at_sq(60), KING as u8);
+    assert_eq!(board.type_at_sq(3), QUEEN as u8);
+    assert_eq!(board.type_at_sq(35), EMPTY as u8);
+
+    for color in [ Color::White, Color::Black ] {
+        assert!(state.has_ksc_right(color));
+        assert!(state.has_qsc_right(color));
+    }
+    assert!(!state.enp_avaliable());
+    assert_eq!(state.get_halfmove_clock(), 0);
+}
+
+#[test]
+fn test_fen_rights() {
+    let board: Board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R b Kq - 0 1").unwrap();
+    let state: &hist_state::HistState = board.state();
+    assert_eq!(board.turn, Color::Black);
+    assert!(state.has_ksc_right(Color::White));
+    assert!(!state.has_qsc_right(Color::White));
+    assert!(!state.has_ksc_right(Color::Black));
+    assert!(state.has_qsc_right(Color::Black));
+
+    let board: Board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1").unwrap();
+    let state: &hist_state::HistState = board.state();
+    for color in [ Color::White, Color::Black ] {
+        assert!(!state.has_ksc_right(color));
+        assert!(!state.has_qsc_right(color));
+    }
+}
+
+#[test]
+fn test_fen_enpassant_and_clocks() {
+    let board: Board = Board::from_fen(
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3"
+    ).unwrap();
+    let state: &hist_state::HistState = board.state();
+    assert!(state.enp_avaliable());
+    assert_eq!(state.get_enp_col(), 5);
+    assert_eq!(board.fullmv_num, 3);
+
+    let board: Board = Board::from_fen(
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+    ).unwrap();
+    assert!(board.state().enp_avaliable());
+    assert_eq!(board.state().get_enp_col(), 4);
+
+    let board: Board = Board::from_fen("8/8/8/8/8/8/8/K1k5 b - - 99 142").unwrap();
+    let state: &hist_state::HistState = board.state();
+    assert_eq!(state.get_halfmove_clock(), 99);
+    assert_eq!(board.fullmv_num, 142);
+    for color in [ Color::White, Color::Black ] {
+        assert!(!state.has_ksc_right(color));
+        assert!(!state.has_qsc_right(color));
+    }
+
+    // Clocks past the fifty-move rule are legal, and the largest ones are capped.
+    let board: Board = Board::from_fen("8/8/8/8/8/8/8/K1k5 b - - 300 250").unwrap();
+    assert_eq!(board.state().get_halfmove_clock(), 300);
+    assert!(!board.state().enp_avaliable());
+    let board: Board = Board::from_fen("8/8/8/8/8/8/8/K1k5 b - - 100000 250").unwrap();
+    assert_eq!(board.state().get_halfmove_clock(), u16::MAX);
+}
+
+#[test]
+fn test_fen_reference_positions() {
+    let fens: [&str; 6] = [
+        START_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+    ];
+
+    for fen in fens {
+        let board = Board::from_fen(fen);
+        assert!(board.is_ok(), "{}: {}", fen, board.err().unwrap());
+    }
+}
+
+#[test]
+fn test_fen_errors() {
+    fn check(fen: &str, expected: fn(&FenError) -> bool) {
+        match Board::from_fen(fen) {
+            Ok(_) => panic!("expected error for {}", fen),
+            Err(e) => assert!(expected(&e), "wrong error for {}: {}", fen, e),
+        }
+    }
+
+    check("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -",
+        |e| matches!(e, FenError::FenMalformedError(_)));
+    check("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 extra",
+        |e| matches!(e, FenError::FenMalformedError(_)));
+    check("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        |e| matches!(e, FenError::FenPlacementError(_)));
+    check("rnbqkbnr/ppppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        |e| matches!(e, FenError::FenPlacementError(_)));
+    check("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1",
+        |e| matches!(e, FenError::FenPlacementError(_)));
+    check("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR/8 w KQkq - 0 1",
+        |e| matches!(e, FenError::FenPlacementError(_)));
+    check("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQQBNR w kq - 0 1",
+        |e| matches!(e, FenError::FenPlacementError(_)));
+    check("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1",
+        |e| matches!(e, FenError::FenTurnError(_)));
+    check("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkx - 0 1",
+        |e| matches!(e, FenError::FenRightsError(_)));
+    check("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KKq - 0 1",
+        |e| matches!(e, FenError::FenRightsError(_)));
+    check("rnbqkbn1/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        |e| matches!(e, FenError::FenRightsError(_)));
+    check("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e6 0 1",
+        |e| matches!(e, FenError::FenEnpassantError(_)));
+    check("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq d3 0 1",
+        |e| matches!(e, FenError::FenEnpassantError(_)));
+    check("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq i3 0 1",
+        |e| matches!(e, FenError::FenEnpassantError(_)));
+    check("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - x 1",
+        |e| matches!(e, FenError::FenHalfmoveError(_)));
+    check("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 0",
+        |e| matches!(e, FenError::FenFullmoveError(_)));
+}
+
+/// Plays the moves on the board, checking that every position survives a trip through a fen
+/// string, and that unmaking all of the moves restores the original position.
+fn check_round_trip(fen: &str, moves: &[Move]) -> Board {
+    let mut board: Board = Board::from_fen(fen).unwrap();
+    assert_eq!(board.to_fen(), fen);
+
+    for mv in moves {
+        board.make(mv);
+        let fen: String = board.to_fen();
+        let reloaded: Board = Board::from_fen(&fen).unwrap();
+        assert_eq!(reloaded, board, "after {}", mv.to_long_algbr());
+        assert_eq!(reloaded.hash(), board.hash(), "after {}", mv.to_long_algbr());
+    }
+
+    let end: Board = Board::from_fen(&board.to_fen()).unwrap();
+    for _ in moves {
+        board.unmake();
+    }
+    assert_eq!(board.to_fen(), fen);
+    assert_eq!(board, Board::from_fen(fen).unwrap());
+    return end;
+}
+
+#[test]
+fn test_fen_round_trip_castles_and_captures() {
+    let end: Board = check_round_trip(START_FEN, &[
+        mv("e2", "e4", DOUBLE_PAWN_PUSH),
+        mv("e7", "e5", DOUBLE_PAWN_PUSH),
+        mv("g1", "f3", QUIET),
+        mv("b8", "c6", QUIET),
+        mv("f1", "c4", QUIET),
+        mv("f8", "c5", QUIET),
+        mv("e1", "g1", KING_SIDE_CASTLE),
+        mv("g8", "f6", QUIET),
+        mv("c4", "f7", CAPTURE),
+        mv("e8", "f7", CAPTURE),
+        mv("f3", "e5", CAPTURE),
+        mv("c6", "e5", CAPTURE),
+    ]);
+    assert_eq!(end.type_at_sq(28), KNIGHT as u8);
+    assert_eq!(end.fullmv_num, 7);
+    assert_eq!(end.to_fen().split(' ').nth(2), Some("-"));
+
+    check_round_trip("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", &[
+        mv("e1", "c1", QUEEN_SIDE_CASTLE),
+        mv("e8", "c8", QUEEN_SIDE_CASTLE),
+        mv("h1", "h8", CAPTURE),
+        mv("d8", "h8", CAPTURE),
+    ]);
+}
+
+#[test]
+fn test_fen_round_trip_enpassant() {
+    let end: Board = check_round_trip(START_FEN, &[
+        mv("e2", "e4", DOUBLE_PAWN_PUSH),
+        mv("a7", "a6", QUIET),
+        mv("e4", "e5", QUIET),
+        mv("d7", "d5", DOUBLE_PAWN_PUSH),
+        mv("e5", "d6", ENPASSANT),
+    ]);
+    assert_eq!(end.to_fen(), "rnbqkbnr/1pp1pppp/p2P4/8/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 3");
+
+    let end: Board = check_round_trip("rnbqkbnr/ppp1pppp/8/8/3p4/8/PPPPPPPP/RNBQKBNR w KQkq - 0 3", &[
+        mv("e2", "e4", DOUBLE_PAWN_PUSH),
+        mv("d4", "e3", ENPASSANT),
+    ]);
+    assert_eq!(end.type_at_sq(36), EMPTY as u8);
+}
+
+#[test]
+fn test_fen_round_trip_promotions() {
+    let end: Board = check_round_trip("r3k2r/1P6/8/8/8/8/1p6/R3K2R w KQkq - 0 1", &[
+        mv("b7", "a8", QUEEN_PROMO_CAPTURE),
+        mv("b2", "a1", KNIGHT_PROMO_CAPTURE),
+        mv("a8", "a1", CAPTURE),
+        mv("e8", "g8", KING_SIDE_CASTLE),
+    ]);
+    assert_eq!(end.to_fen(), "5rk1/8/8/8/8/8/8/Q3K2R w K - 1 3");
+
+    check_round_trip("4k3/P7/8/8/8/8/7p/4K3 w - - 0 1", &[
+        mv("a7", "a8", ROOK_PROMO),
+        mv("h2", "h1", BISHOP_PROMO),
+        mv("a8", "a1", QUIET),
+        mv("h1", "g2", QUIET),
+    ]);
+}
+
+/// Checks the incremental hash against a full recompute at every node of a small move tree.
+fn check_hash_tree(board: &mut Board, generator: &MoveGenerator, depth: u8) {
+    assert_eq!(board.hash(), board.compute_hash(), "{}", board.to_fen());
+    if depth == 0 {
+        return;
+    }
+
+    let mut moves: MoveList = MoveList::new();
+    let state: BoardTables = generator.gen_board_tables(board);
+    generator.gen_moves(&mut moves, board, &state);
+    for i in 0..moves.size() {
+        let hash: u64 = board.hash();
+        board.make(moves.at(i));
+        check_hash_tree(board, generator, depth - 1);
+        board.unmake();
+        assert_eq!(board.hash(), hash);
+    }
+}
+
+#[test]
+fn test_hash_incremental() {
+    let generator: MoveGenerator = MoveGenerator::new();
+    for fen in [
+        START_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    ] {
+        let mut board: Board = Board::from_fen(fen).unwrap();
+        check_hash_tree(&mut board, &generator, 3);
+    }
+}
+
+#[test]
+fn test_hash_transpositions() {
+    let start: u64 = Board::from_fen(START_FEN).unwrap().hash();
+
+    // Moves that come back to the same position give the same hash, regardless of move counters.
+    let mut board: Board = Board::from_fen(START_FEN).unwrap();
+    for (from, to) in [ ("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8") ] {
+        board.make(&mv(from, to, QUIET));
+    }
+    assert_eq!(board.hash(), start);
+    assert_ne!(board.fullmv_num, 1);
+
+    // A double push transposes with two single pushes unless the enpassant can be taken.
+    let mut double: Board = Board::from_fen(START_FEN).unwrap();
+    double.make(&mv("e2", "e4", DOUBLE_PAWN_PUSH));
+    let mut single: Board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/4P3/PPPP1PPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    single.make(&mv("e3", "e4", QUIET));
+    assert_eq!(double.hash(), single.hash());
+
+    let with_enp: Board = Board::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1").unwrap();
+    let without_enp: Board = Board::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1").unwrap();
+    assert_ne!(with_enp.hash(), without_enp.hash());
+}
+
+#[test]
+fn test_hash_distinguishes_state() {
+    let hash = |fen: &str| Board::from_fen(fen).unwrap().hash();
+
+    assert_ne!(hash("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"), hash("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1"));
+    assert_ne!(hash("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"), hash("r3k2r/8/8/8/8/8/8/R3K2R w KQk - 0 1"));
+    assert_ne!(hash("r3k2r/8/8/8/8/8/8/R3K2R w Kk - 0 1"), hash("r3k2r/8/8/8/8/8/8/R3K2R w Qq - 0 1"));
+    assert_ne!(hash("4k3/8/8/8/8/8/8/4K2R w - - 0 1"), hash("4k3/8/8/8/8/8/8/4K2r w - - 0 1"));
+    assert_ne!(hash("4k3/8/8/8/8/8/8/4K2R w - - 0 1"), hash("4k3/8/8/8/8/8/8/4K2Q w - - 0 1"));
+    assert_eq!(hash("4k3/8/8/8/8/8/8/4K2R w - - 0 1"), hash("4k3/8/8/8/8/8/8/4K2R w - - 12 40"));
+}
+
+#[test]
+fn test_halfmove_clock() {
+    let mut board: Board = Board::from_fen("r3k2r/pppq1ppp/8/8/8/8/PPPQ1PPP/R3K2R w KQkq - 7 20").unwrap();
+
+    board.make(&mv("e1", "g1", KING_SIDE_CASTLE));
+    assert_eq!(board.state().get_halfmove_clock(), 8);
+    board.make(&mv("a7", "a6", QUIET));
+    assert_eq!(board.state().get_halfmove_clock(), 0);
+    board.make(&mv("d2", "d4", QUIET));
+    assert_eq!(board.state().get_halfmove_clock(), 1);
+    board.make(&mv("d7", "d4", CAPTURE));
+    assert_eq!(board.state().get_halfmove_clock(), 0);
+    assert_eq!(board.to_fen(), "r3k2r/1pp2ppp/p7/8/3q4/8/PPP2PPP/R4RK1 w kq - 0 22");
+
+    for _ in 0..4 {
+        board.unmake();
+    }
+    assert_eq!(board.state().get_halfmove_clock(), 7);
+}
+
+#[test]
+fn test_fifty_move_draw() {
+    let mut board: Board = Board::from_fen("4k3/8/8/8/8/8/4P3/R3K3 w - - 99 80").unwrap();
+    assert!(!board.is_fifty_move_draw());
+
+    board.make(&mv("a1", "a2", QUIET));
+    assert!(board.is_fifty_move_draw());
+    board.unmake();
+
+    board.make(&mv("e2", "e3", QUIET));
+    assert!(!board.is_fifty_move_draw());
+    board.unmake();
+
+    assert!(Board::from_fen("4k3/8/8/8/8/8/4P3/R3K3 w - - 120 80").unwrap().is_fifty_move_draw());
+}
+
+#[test]
+fn test_repetition() {
+    let mut board: Board = Board::from_fen(START_FEN).unwrap();
+    let shuffle: [Move; 4] = [
+        mv("g1", "f3", QUIET),
+        mv("g8", "f6", QUIET),
+        mv("f3", "g1", QUIET),
+        mv("f6", "g8", QUIET),
+    ];
+
+    assert!(board.is_repetition(1));
+    assert!(!board.is_repetition(2));
+    for mv in &shuffle {
+        board.make(mv);
+    }
+    assert!(board.is_repetition(2));
+    assert!(!board.is_repetition(3));
+
+    // The positions in between only repeat for the same player to move.
+    board.make(&shuffle[0]);
+    assert!(board.is_repetition(2));
+    board.unmake();
+
+    for mv in &shuffle {
+        board.make(mv);
+    }
+    assert!(board.is_repetition(3));
+
+    // Nothing before an irreversible move can repeat.
+    board.make(&mv("e2", "e3", QUIET));
+    board.make(&mv("e7", "e6", QUIET));
+    for mv in &shuffle {
+        board.make(mv);
+    }
+    assert!(board.is_repetition(2));
+    assert!(!board.is_repetition(3));
+}
+
+#[test]
+fn test_repetition_needs_same_rights() {
+    // Moving the king and back loses the castle rights, so the position doesn't repeat.
+    let mut board: Board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    for (from, to) in [ ("e1", "f1"), ("e8", "f8"), ("f1", "e1"), ("f8", "e8") ] {
+        board.make(&mv(from, to, QUIET));
+    }
+    assert!(!board.is_repetition(2));
+
+    for (from, to) in [ ("e1", "f1"), ("e8", "f8"), ("f1", "e1"), ("f8", "e8") ] {
+        board.make(&mv(from, to, QUIET));
+    }
+    assert!(board.is_repetition(2));
+}
+
+#[test]
+fn test_try_make() {
+    let mut board: Board = Board::from_fen(START_FEN).unwrap();
+    assert_eq!(board.try_make(&mv("e2", "e4", DOUBLE_PAWN_PUSH)), Ok(()));
+    assert_eq!(board.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+
+    // Rejected moves leave the board as it was.
+    let rejected: [Move; 5] = [
+        mv("e7", "e5", QUIET),
+        mv("e7", "e6", DOUBLE_PAWN_PUSH),
+        mv("d8", "d4", QUIET),
+        mv("e2", "e3", QUIET),
+        mv("g8", "f6", CAPTURE),
+    ];
+    for illegal in &rejected {
+        assert_eq!(board.try_make(illegal), Err(IllegalMove::IllegalMoveError(illegal.clone())));
+    }
+    let malformed: Move = mv("e7", "e5", 6 << 12);
+    assert_eq!(board.try_make(&malformed), Err(IllegalMove::IllegalFlagsError(malformed.clone())));
+    assert_eq!(board.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+    assert_eq!(board.hash(), board.compute_hash());
+
+    assert_eq!(board.try_make(&mv("e7", "e5", DOUBLE_PAWN_PUSH)), Ok(()));
+}
+
+#[test]
+fn test_try_make_special_moves() {
+    // A pawn on the seventh row has to promote, and only castles with the castle flags.
+    let mut board: Board = Board::from_fen("4k3/1P6/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+    assert!(board.try_make(&mv("b7", "b8", QUIET)).is_err());
+    assert!(board.try_make(&mv("e1", "g1", QUIET)).is_err());
+    assert!(board.try_make(&mv("e1", "c1", KING_SIDE_CASTLE)).is_err());
+    assert_eq!(board.try_make(&mv("e1", "g1", KING_SIDE_CASTLE)), Ok(()));
+    board.unmake();
+    assert_eq!(board.try_make(&mv("b7", "b8", QUEEN_PROMO)), Ok(()));
+}
+
+#[test]
+fn test_make_null() {
+    let fen: &str = "rnbqkbnr/ppp1pppp/8/8/3pP3/5N2/PPPP1PPP/RNBQKB1R b KQkq e3 0 3";
+    let mut board: Board = Board::from_fen(fen).unwrap();
+    board.make_null();
+    assert_eq!(board.to_fen(), "rnbqkbnr/ppp1pppp/8/8/3pP3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 1 4");
+    assert_eq!(board.hash(), board.compute_hash());
+    assert!(!board.is_repetition(2));
+
+    // The same position reached by moves hashes the same.
+    board.make(&mv("f3", "g1", QUIET));
+    board.make_null();
+    board.make(&mv("g1", "f3", QUIET));
+    let expected: &str = "rnbqkbnr/ppp1pppp/8/8/3pP3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 4 5";
+    assert_eq!(board.hash(), Board::from_fen(expected).unwrap().hash());
+
+    board.unmake();
+    board.unmake_null();
+    board.unmake();
+    board.unmake_null();
+    assert_eq!(board.to_fen(), fen);
+    assert_eq!(board.hash(), Board::from_fen(fen).unwrap().hash());
+}
+
+#[test]
+fn test_make_null_draws() {
+    // The fifty-move rule still counts the null move.
+    let mut board: Board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 b - - 99 80").unwrap();
+    board.make_null();
+    assert!(board.is_fifty_move_draw());
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/R3K3 w - - 100 81");
+    board.unmake_null();
+
+    // A position from before the null move doesn't repeat after it, one from after it does.
+    let mut board: Board = Board::from_fen(START_FEN).unwrap();
+    board.make(&mv("g1", "f3", QUIET));
+    board.make_null();
+    board.make(&mv("f3", "g1", QUIET));
+    board.make_null();
+    assert_eq!(board.hash(), Board::from_fen(START_FEN).unwrap().hash());
+    assert!(!board.is_repetition(2));
+    for (from, to) in [ ("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8") ] {
+        board.make(&mv(from, to, QUIET));
+    }
+    assert!(board.is_repetition(2));
+    assert_eq!(board.state().get_halfmove_clock(), 8);
+}
+
+#[test]
+fn test_clone() {
+    let mut board: Board = Board::from_fen(START_FEN).unwrap();
+    board.make(&mv("e2", "e4", DOUBLE_PAWN_PUSH));
+    let mut clone: Board = board.clone();
+    assert_eq!(clone, board);
+    assert_eq!(clone.hash(), board.hash());
+
+    // The clone has its own pieces and history.
+    clone.make(&mv("e7", "e5", DOUBLE_PAWN_PUSH));
+    assert_ne!(clone, board);
+    clone.unmake();
+    clone.unmake();
+    assert_eq!(clone, Board::from_fen(START_FEN).unwrap());
+    assert_eq!(board.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+}
+
+#[test]
+fn test_from_uci() {
+    let board: Board = Board::from_uci("startpos").unwrap();
+    assert_eq!(board.to_fen(), START_FEN);
+
+    let board: Board = Board::from_uci("startpos moves e2e4 c7c5 g1f3").unwrap();
+    assert_eq!(board.to_fen(), "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+    assert_eq!(board.history.data.len(), 4);
+
+    // Special moves get their flags from the position.
+    let fen: &str = "r3k2r/6P1/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    let board: Board = Board::from_uci(&format!("fen {} moves e1g1 e8c8 g7g8q", fen)).unwrap();
+    assert_eq!(board.to_fen(), "2kr2Qr/8/8/8/8/8/8/R4RK1 b - - 0 2");
+
+    // The moves are kept in the history, so the repetition is recognized.
+    let board: Board = Board::from_uci("startpos moves g1f3 g8f6 f3g1 f6g8 g1f3").unwrap();
+    assert!(board.is_repetition(2));
+
+    assert!(Board::from_uci("startpos moves").is_ok());
+    assert!(matches!(Board::from_uci(""), Err(UciError::UciMalformedError(_))));
+    assert!(matches!(Board::from_uci("fen moves e2e4"), Err(UciError::UciMalformedError(_))));
+    assert!(matches!(Board::from_uci("startpos e2e4"), Err(UciError::UciMalformedError(_))));
+    assert!(matches!(Board::from_uci("fen 8/8/8 w - - 0 1"), Err(UciError::UciFenError(_))));
+    assert!(matches!(Board::from_uci("startpos moves e2e5"),
+        Err(UciError::UciIllegalMoveError(_))));
+    assert!(matches!(Board::from_uci("startpos moves e2"), Err(UciError::UciMalformedError(_))));
+}
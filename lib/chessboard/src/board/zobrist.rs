@@ -0,0 +1,83 @@
+
+use crate::types::*;
+use std::sync::OnceLock;
+
+/// Seed for the key generator. The keys must be the same on every run so that hashes can be
+/// compared between runs (e.g. in tests and opening books).
+const SEED: u64 = 0x5EED_C0FF_EE15_600D;
+
+/// Random keys that are xor'd together to build the zobrist hash of a position.
+pub struct ZobristKeys {
+    pieces: [[[u64; 64]; 6]; 2],
+    side: u64,
+    castle: [u64; 16],
+    enp: [u64; 8]
+}
+
+/// The splitmix64 generator, which is small and produces well distributed 64 bit values.
+fn next_key(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z: u64 = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    return z ^ (z >> 31);
+}
+
+impl ZobristKeys {
+    pub fn get_instance() -> &'static Self {
+        static KEYS: OnceLock<ZobristKeys> = OnceLock::new();
+        KEYS.get_or_init(ZobristKeys::new)
+    }
+
+    fn new() -> Self {
+        let mut state: u64 = SEED;
+        let mut keys: ZobristKeys = ZobristKeys {
+            pieces: [[[0; 64]; 6]; 2],
+            side: 0,
+            castle: [0; 16],
+            enp: [0; 8]
+        };
+
+        for color in keys.pieces.iter_mut() {
+            for ptype in color.iter_mut() {
+                for key in ptype.iter_mut() {
+                    *key = next_key(&mut state);
+                }
+            }
+        }
+        keys.side = next_key(&mut state);
+        for key in keys.castle.iter_mut() {
+            *key = next_key(&mut state);
+        }
+        for key in keys.enp.iter_mut() {
+            *key = next_key(&mut state);
+        }
+
+        return keys;
+    }
+
+    /// Reads the key for a piece on a square.
+    pub fn piece(&self, sq: Square, ptype: PieceType, pcolor: Color) -> u64 {
+        return self.piece_sq(sq.index(), ptype as u8, pcolor);
+    }
+
+    /// Reads the key for a piece given by its raw square and type, as make and unmake have them.
+    pub(crate) fn piece_sq(&self, sq: u8, ptype: u8, pcolor: Color) -> u64 {
+        return self.pieces[pcolor as usize][ptype as usize][sq as usize];
+    }
+
+    /// Reads the key that is xor'd in when black is to move.
+    pub fn side(&self) -> u64 {
+        return self.side;
+    }
+
+    /// Reads the key for a set of castle rights as stored in the low bits of the HistState.
+    pub fn castle(&self, rights: u8) -> u64 {
+        return self.castle[rights as usize];
+    }
+
+    /// Reads the key for an enpassant column.
+    pub fn enp(&self, col: u8) -> u64 {
+        return self.enp[col as usize];
+    }
+}
@@ -0,0 +1,72 @@
+
+use crate::board::*;
+use crate::eval::*;
+use crate::eval::mobility::piece_attacks;
+use crate::eval::pawns::{adjacent_cols, rows_in_front};
+use crate::generator::tables::MoveTables;
+
+/// Weight of an attack on a square next to the king, indexed by the type of the attacker.
+pub const ATTACK_WEIGHTS: [i32; 6] = [ 0, 2, 2, 3, 5, 0 ];
+/// Bonus for each pawn right in front of the king (on its column or the ones next to it), and for
+/// the ones a row further away.
+pub const SHIELD_CLOSE: Score = Score::new(12, 0);
+pub const SHIELD_FAR: Score = Score::new(6, 0);
+
+/// The king safety terms of one color.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KingSafety {
+    pub shield: Score,
+    pub attacks: Score
+}
+
+/// Scores how safe the king of a color is. Enemy pieces attacking the squares around the king
+/// cost more the more of them there are, and pawns in front of the king protect it. Both matter
+/// only while there are pieces on the board to attack with.
+pub fn evaluate_king_safety(board: &Board, pcolor: Color) -> KingSafety {
+    let tables: &MoveTables = MoveTables::get_instance();
+    let king_sq: u8 = bitboard::peek_rbit(&board.bitboard.piece[pcolor as usize][KING]);
+    let zone: u64 = tables.read_king_attacks(king_sq) | (1u64 << king_sq);
+    let enemy: Color = pcolor.opposite();
+
+    // Count up the weighted attacks on the zone around the king.
+    let mut attackers: i32 = 0;
+    let mut weight: i32 = 0;
+    for ptype in KNIGHT..=QUEEN {
+        let mut pieces: u64 = board.bitboard.piece[enemy as usize][ptype];
+        while pieces != 0 {
+            let sq: u8 = bitboard::pop_rbit(&mut pieces);
+            let hits: u64 = piece_attacks(tables, sq, ptype, board.bitboard.occupancy) & zone;
+            if hits != 0 {
+                attackers += 1;
+                weight += ATTACK_WEIGHTS[ptype] * bitboard::popcnt(&hits) as i32;
+            }
+        }
+    }
+    // A single attacker is rarely dangerous on its own.
+    let attacks: Score = if attackers >= 2 {
+        Score::new(-weight * weight / 4, 0)
+    } else {
+        Score::default()
+    };
+
+    // Look for pawns on the two rows in front of the king.
+    let (row, col): (u8, u8) = (king_sq / 8, king_sq % 8);
+    let cols: u64 = (bitboard::LEFT_COL << col) | adjacent_cols(col);
+    let mut pawns: u64 = board.bitboard.piece[pcolor as usize][PAWN] & cols;
+    pawns &= rows_in_front(row, pcolor);
+    let mut shield: Score = Score::default();
+    while pawns != 0 {
+        let sq: u8 = bitboard::pop_rbit(&mut pawns);
+        let distance: u8 = (sq / 8).abs_diff(row);
+        if distance == 1 {
+            shield += SHIELD_CLOSE;
+        } else if distance == 2 {
+            shield += SHIELD_FAR;
+        }
+    }
+
+    return KingSafety {
+        shield,
+        attacks
+    };
+}
@@ -0,0 +1,53 @@
+
+use crate::board::*;
+use crate::eval::*;
+use crate::generator::tables::MoveTables;
+
+/// Score per square a piece can move to, indexed by piece type. Pawns and kings don't count.
+pub const MOBILITY: [Score; 6] = [
+    Score::new(0, 0),
+    Score::new(4, 4),
+    Score::new(5, 5),
+    Score::new(2, 4),
+    Score::new(1, 2),
+    Score::new(0, 0),
+];
+
+/// Roughly the number of squares a piece of each type reaches in a typical position. Pieces
+/// that reach more squares than this get a bonus, the others a penalty.
+pub const MOBILITY_BASE: [i32; 6] = [ 0, 4, 7, 7, 14, 0 ];
+
+/// Gets the squares a piece attacks through the current occupancy.
+pub(crate) fn piece_attacks(tables: &MoveTables, sq: u8, ptype: usize, occupancy: u64) -> u64 {
+    return match ptype {
+        KNIGHT => tables.read_knight_attacks(sq),
+        BISHOP => tables.read_bishop_attacks(sq, occupancy),
+        ROOK => tables.read_rook_attacks(sq, occupancy),
+        QUEEN => {
+            tables.read_bishop_attacks(sq, occupancy) | tables.read_rook_attacks(sq, occupancy)
+        },
+        KING => tables.read_king_attacks(sq),
+        _ => unreachable!()
+    };
+}
+
+/// Scores the pieces of a color by the number of squares they can move to. Squares with our own
+/// pieces or attacked by enemy pawns aren't counted, as the pieces can't really go there.
+pub fn evaluate_mobility(board: &Board, pcolor: Color) -> Score {
+    let tables: &MoveTables = MoveTables::get_instance();
+    let own: u64 = board.bitboard.color[pcolor as usize];
+    let enemy_pawns: u64 = board.bitboard.piece[pcolor.opposite() as usize][PAWN];
+    let safe: u64 = !own & !bitboard::pawn_smear(enemy_pawns, pcolor == Color::Black);
+
+    let mut score: Score = Score::default();
+    for ptype in KNIGHT..=QUEEN {
+        let mut pieces: u64 = board.bitboard.piece[pcolor as usize][ptype];
+        while pieces != 0 {
+            let sq: u8 = bitboard::pop_rbit(&mut pieces);
+            let attacks: u64 = piece_attacks(tables, sq, ptype, board.bitboard.occupancy);
+            let count: i32 = bitboard::popcnt(&(attacks & safe)) as i32;
+            score += MOBILITY[ptype] * (count - MOBILITY_BASE[ptype]);
+        }
+    }
+    return score;
+}
@@ -0,0 +1,267 @@
+
+use crate::board::*;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+pub mod king;
+pub mod mobility;
+pub mod pawns;
+pub mod pst;
+
+use king::KingSafety;
+use pawns::{PawnScores, PawnTable};
+
+/// Number of entries in the pawn table of an Evaluator.
+pub const PAWN_TABLE_ENTRIES: usize = 1 << 14;
+
+#[cfg(test)]
+mod tests;
+
+/// A pair of middlegame and endgame scores, which are blended by the game phase in the end.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Score {
+    pub mg: i32,
+    pub eg: i32
+}
+
+impl Score {
+    pub const fn new(mg: i32, eg: i32) -> Self {
+        Score {
+            mg,
+            eg
+        }
+    }
+
+    pub fn taper(self, phase: i32) -> i32 {
+        return taper(self.mg, self.eg, phase);
+    }
+}
+
+impl Add for Score {
+    type Output = Score;
+
+    fn add(self, other: Score) -> Score {
+        return Score::new(self.mg + other.mg, self.eg + other.eg);
+    }
+}
+
+impl AddAssign for Score {
+    fn add_assign(&mut self, other: Score) {
+        self.mg += other.mg;
+        self.eg += other.eg;
+    }
+}
+
+impl Sub for Score {
+    type Output = Score;
+
+    fn sub(self, other: Score) -> Score {
+        return Score::new(self.mg - other.mg, self.eg - other.eg);
+    }
+}
+
+impl Neg for Score {
+    type Output = Score;
+
+    fn neg(self) -> Score {
+        return Score::new(-self.mg, -self.eg);
+    }
+}
+
+impl Mul<i32> for Score {
+    type Output = Score;
+
+    fn mul(self, factor: i32) -> Score {
+        return Score::new(self.mg * factor, self.eg * factor);
+    }
+}
+
+/// The material and piece-square sums of a position for both colors, in the middlegame and the
+/// endgame, along with the game phase. The board keeps one up to date as pieces are written and
+/// deleted, so the evaluation doesn't have to look at every piece.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Accumulator {
+    mg: [i32; 2],
+    eg: [i32; 2],
+    phase: i32
+}
+
+/// Gets the index into the piece-square tables for a piece of the given color.
+fn pst_index(sq: u8, pcolor: Color) -> usize {
+    return if pcolor == Color::White { sq as usize } else { (sq ^ 56) as usize };
+}
+
+impl Accumulator {
+    pub fn new() -> Self {
+        Accumulator {
+            mg: [0; 2],
+            eg: [0; 2],
+            phase: 0
+        }
+    }
+
+    /// Sums up the pieces of a board from scratch.
+    pub fn from_board(board: &Board) -> Self {
+        let mut acc: Accumulator = Accumulator::new();
+        for sq in Square::all() {
+            if let Some(piece) = board.piece_at(sq) {
+                acc.add(sq.index(), piece.ptype.index() as u8, piece.color);
+            }
+        }
+        return acc;
+    }
+
+    pub(crate) fn add(&mut self, sq: u8, ptype: u8, pcolor: Color) {
+        let idx: usize = pst_index(sq, pcolor);
+        let ptype: usize = ptype as usize;
+        self.mg[pcolor as usize] += pst::MG_VALUES[ptype] + pst::MG_TABLES[ptype][idx];
+        self.eg[pcolor as usize] += pst::EG_VALUES[ptype] + pst::EG_TABLES[ptype][idx];
+        self.phase += pst::PHASE_WEIGHTS[ptype];
+    }
+
+    pub(crate) fn remove(&mut self, sq: u8, ptype: u8, pcolor: Color) {
+        let idx: usize = pst_index(sq, pcolor);
+        let ptype: usize = ptype as usize;
+        self.mg[pcolor as usize] -= pst::MG_VALUES[ptype] + pst::MG_TABLES[ptype][idx];
+        self.eg[pcolor as usize] -= pst::EG_VALUES[ptype] + pst::EG_TABLES[ptype][idx];
+        self.phase -= pst::PHASE_WEIGHTS[ptype];
+    }
+
+    /// Gets the middlegame material and piece-square sum of a color.
+    pub fn mg(&self, pcolor: Color) -> i32 {
+        return self.mg[pcolor as usize];
+    }
+
+    /// Gets the endgame material and piece-square sum of a color.
+    pub fn eg(&self, pcolor: Color) -> i32 {
+        return self.eg[pcolor as usize];
+    }
+
+    /// Gets the game phase, from MAX_PHASE with all pieces on the board down to 0 with only
+    /// pawns and kings. Promotions can push it above MAX_PHASE, so it is capped.
+    pub fn phase(&self) -> i32 {
+        return self.phase.min(pst::MAX_PHASE);
+    }
+
+    /// Blends the middlegame and endgame scores by the game phase, from white's perspective.
+    pub fn tapered(&self) -> i32 {
+        let mg: i32 = self.mg[WHITE] - self.mg[BLACK];
+        let eg: i32 = self.eg[WHITE] - self.eg[BLACK];
+        return taper(mg, eg, self.phase());
+    }
+}
+
+/// Blends a middlegame and an endgame score by the game phase.
+pub fn taper(mg: i32, eg: i32, phase: i32) -> i32 {
+    return (mg * phase + eg * (pst::MAX_PHASE - phase)) / pst::MAX_PHASE;
+}
+
+/// The terms that make up the evaluation of a position, for each color. Useful for finding out
+/// why a position is evaluated the way it is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Trace {
+    /// Material and piece-square tables.
+    pub material: [Score; 2],
+    pub pawns: PawnScores,
+    pub mobility: [Score; 2],
+    pub king_safety: [KingSafety; 2],
+    pub phase: i32
+}
+
+impl Trace {
+    /// Sums up all of the terms of one color.
+    pub fn sum(&self, pcolor: Color) -> Score {
+        let c: usize = pcolor as usize;
+        return self.material[c]
+            + self.pawns.doubled[c] + self.pawns.isolated[c] + self.pawns.backward[c]
+            + self.pawns.passed[c]
+            + self.mobility[c]
+            + self.king_safety[c].shield + self.king_safety[c].attacks;
+    }
+
+    /// Gets the evaluation in centipawns from the perspective of the given color.
+    pub fn score(&self, pcolor: Color) -> i32 {
+        let white: i32 = (self.sum(Color::White) - self.sum(Color::Black)).taper(self.phase);
+        return if pcolor == Color::White { white } else { -white };
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms: [(&str, [Score; 2]); 9] = [
+            ("Material", self.material),
+            ("Doubled pawns", self.pawns.doubled),
+            ("Isolated pawns", self.pawns.isolated),
+            ("Backward pawns", self.pawns.backward),
+            ("Passed pawns", self.pawns.passed),
+            ("Mobility", self.mobility),
+            ("King shield", [ self.king_safety[WHITE].shield, self.king_safety[BLACK].shield ]),
+            ("King attacks", [ self.king_safety[WHITE].attacks, self.king_safety[BLACK].attacks ]),
+            ("Total", [ self.sum(Color::White), self.sum(Color::Black) ]),
+        ];
+
+        writeln!(f, "{:<16}|{:>13} |{:>13} |{:>13}", "Term", "White", "Black", "Tapered")?;
+        writeln!(f, "{:<16}|{:>6} {:>6} |{:>6} {:>6} |", "", "MG", "EG", "MG", "EG")?;
+        for (name, score) in terms {
+            let white: Score = score[WHITE];
+            let black: Score = score[BLACK];
+            writeln!(f, "{:<16}|{:>6} {:>6} |{:>6} {:>6} |{:>13}", name, white.mg, white.eg,
+                black.mg, black.eg, (white - black).taper(self.phase))?;
+        }
+        write!(f, "Phase {} of {}", self.phase, pst::MAX_PHASE)
+    }
+}
+
+/// Evaluates a position with its pawn structure already known.
+fn trace_with_pawns(board: &Board, pawns: PawnScores) -> Trace {
+    let mut trace: Trace = Trace {
+        pawns,
+        phase: board.psqt.phase(),
+        ..Default::default()
+    };
+    for pcolor in [ Color::White, Color::Black ] {
+        let c: usize = pcolor as usize;
+        trace.material[c] = Score::new(board.psqt.mg(pcolor), board.psqt.eg(pcolor));
+        trace.mobility[c] = mobility::evaluate_mobility(board, pcolor);
+        trace.king_safety[c] = king::evaluate_king_safety(board, pcolor);
+    }
+    return trace;
+}
+
+/// Breaks the evaluation of a position down into its terms.
+pub fn trace(board: &Board) -> Trace {
+    return trace_with_pawns(board, pawns::evaluate_pawns(board));
+}
+
+/// Statically evaluates a position in centipawns from the perspective of the side to move.
+pub fn evaluate(board: &Board) -> i32 {
+    return trace(board).score(board.turn);
+}
+
+/// Evaluates positions like evaluate, but caches the pawn structure of the positions it sees.
+/// A search should keep one of these around instead of calling evaluate.
+pub struct Evaluator {
+    pawn_table: PawnTable
+}
+
+impl Evaluator {
+    pub fn new() -> Self {
+        Evaluator {
+            pawn_table: PawnTable::new(PAWN_TABLE_ENTRIES)
+        }
+    }
+
+    pub fn trace(&mut self, board: &Board) -> Trace {
+        let pawns: PawnScores = self.pawn_table.probe(board);
+        return trace_with_pawns(board, pawns);
+    }
+
+    pub fn evaluate(&mut self, board: &Board) -> i32 {
+        return self.trace(board).score(board.turn);
+    }
+
+    /// Forgets the cached pawn structures, e.g. before a new game.
+    pub fn clear(&mut self) {
+        self.pawn_table.clear();
+    }
+}
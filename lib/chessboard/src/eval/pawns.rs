@@ -0,0 +1,125 @@
+
+use crate::board::*;
+use crate::eval::*;
+
+pub const DOUBLED: Score = Score::new(-10, -20);
+pub const ISOLATED: Score = Score::new(-10, -15);
+pub const BACKWARD: Score = Score::new(-8, -10);
+/// Bonus for a passed pawn, indexed by how many rows it has advanced from its home row.
+pub const PASSED: [Score; 7] = [
+    Score::new(0, 0),
+    Score::new(5, 10),
+    Score::new(10, 20),
+    Score::new(15, 35),
+    Score::new(30, 60),
+    Score::new(50, 100),
+    Score::new(80, 150),
+];
+
+/// The pawn structure terms of both colors, indexed by color.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PawnScores {
+    pub doubled: [Score; 2],
+    pub isolated: [Score; 2],
+    pub backward: [Score; 2],
+    pub passed: [Score; 2]
+}
+
+#[derive(Clone, Copy, Default)]
+struct PawnEntry {
+    key: u64,
+    scores: PawnScores
+}
+
+/// Caches the pawn structure evaluation by pawn hash. The pawns change much less often than the
+/// rest of the position, so most evaluations find their pawn structure here.
+pub struct PawnTable {
+    entries: Vec<PawnEntry>
+}
+
+impl PawnTable {
+    /// Creates a table with room for the given number of entries, rounded up to a power of two.
+    pub fn new(entries: usize) -> Self {
+        PawnTable {
+            entries: vec![PawnEntry::default(); entries.max(1).next_power_of_two()]
+        }
+    }
+
+    /// Gets the pawn structure of a board, evaluating and storing it if it isn't cached. Empty
+    /// entries have a key of 0, which is also the hash of a board without pawns, but that is fine
+    /// because the scores of a board without pawns are all 0.
+    pub fn probe(&mut self, board: &Board) -> PawnScores {
+        let idx: usize = (board.pawn_hash as usize) & (self.entries.len() - 1);
+        let entry: &mut PawnEntry = &mut self.entries[idx];
+        if entry.key != board.pawn_hash {
+            entry.key = board.pawn_hash;
+            entry.scores = evaluate_pawns(board);
+        }
+        return entry.scores;
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(PawnEntry::default());
+    }
+}
+
+/// Gets the mask of the rows in front of a row, as seen by the given color.
+pub(crate) fn rows_in_front(row: u8, pcolor: Color) -> u64 {
+    return if pcolor == Color::White {
+        (1u64 << (row * 8)) - 1
+    } else if row == 7 {
+        0
+    } else {
+        !((1u64 << ((row + 1) * 8)) - 1)
+    };
+}
+
+/// Gets the mask of the columns next to a column.
+pub(crate) fn adjacent_cols(col: u8) -> u64 {
+    let file: u64 = bitboard::LEFT_COL << col;
+    return ((file << 1) & !bitboard::LEFT_COL) | ((file >> 1) & !bitboard::RIGHT_COL);
+}
+
+/// Evaluates the pawn structure of both colors. It only depends on the pawns, which is what makes
+/// it possible to cache it by pawn hash.
+pub fn evaluate_pawns(board: &Board) -> PawnScores {
+    let mut scores: PawnScores = PawnScores::default();
+
+    for pcolor in [ Color::White, Color::Black ] {
+        let own: u64 = board.bitboard.piece[pcolor as usize][PAWN];
+        let enemy: u64 = board.bitboard.piece[pcolor.opposite() as usize][PAWN];
+        let enemy_attacks: u64 = bitboard::pawn_smear(enemy, pcolor == Color::Black);
+        let c: usize = pcolor as usize;
+
+        let mut pawns: u64 = own;
+        while pawns != 0 {
+            let sq: u8 = bitboard::pop_rbit(&mut pawns);
+            let (row, col): (u8, u8) = (sq / 8, sq % 8);
+            let file: u64 = bitboard::LEFT_COL << col;
+            let adjacent: u64 = adjacent_cols(col);
+            let in_front: u64 = rows_in_front(row, pcolor);
+
+            if own & file & in_front != 0 {
+                scores.doubled[c] += DOUBLED;
+            }
+
+            if own & adjacent == 0 {
+                scores.isolated[c] += ISOLATED;
+            } else if own & adjacent & !in_front == 0 {
+                // No pawn next to or behind it can defend it as it advances, and it can't advance
+                // safely either.
+                let stop: u8 = if pcolor == Color::White { sq - 8 } else { sq + 8 };
+                if enemy_attacks & (1u64 << stop) != 0 {
+                    scores.backward[c] += BACKWARD;
+                }
+            }
+
+            if enemy & (file | adjacent) & in_front == 0 {
+                let advanced: u8 = if pcolor == Color::White { 6 - row } else { row - 1 };
+                scores.passed[c] += PASSED[advanced as usize];
+            }
+        }
+    }
+
+    return scores;
+}
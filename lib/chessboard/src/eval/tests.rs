@@ -0,0 +1,158 @@
+use crate::board::*;
+use crate::eval::*;
+use crate::generator::*;
+use crate::moves::*;
+
+const POSITIONS: [&str; 5] = [
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+];
+
+/// Flips a position vertically and swaps the colors of all the pieces.
+fn mirror_fen(fen: &str) -> String {
+    let fields: Vec<&str> = fen.split_whitespace().collect();
+    let swap_case = |s: &str| -> String {
+        return s.chars().map(|c| {
+            if c.is_ascii_uppercase() { c.to_ascii_lowercase() } else { c.to_ascii_uppercase() }
+        }).collect();
+    };
+
+    let rows: Vec<&str> = fields[0].split('/').rev().collect();
+    let turn: &str = if fields[1] == "w" { "b" } else { "w" };
+    let enp: String = fields[3].chars().map(|c| match c {
+        '3' => '6',
+        '6' => '3',
+        c => c
+    }).collect();
+    return format!("{} {} {} {} {} {}", swap_case(&rows.join("/")), turn, swap_case(fields[2]),
+        enp, fields[4], fields[5]);
+}
+
+#[test]
+fn test_evaluate_symmetric() {
+    let board: Board = Board::from_fen(START_FEN).unwrap();
+    assert_eq!(evaluate(&board), 0);
+    assert_eq!(board.psqt.phase(), pst::MAX_PHASE);
+
+    for fen in &POSITIONS {
+        let board: Board = Board::from_fen(fen).unwrap();
+        let mirrored: Board = Board::from_fen(&mirror_fen(fen)).unwrap();
+        assert_eq!(evaluate(&board), evaluate(&mirrored), "{}", fen);
+    }
+}
+
+#[test]
+fn test_evaluate_perspective() {
+    // White is a queen up, which is good for white and bad for black.
+    let white: Board = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+    let black: Board = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 b - - 0 1").unwrap();
+    assert!(evaluate(&white) > 800);
+    assert_eq!(evaluate(&black), -evaluate(&white));
+
+    // Only kings and pawns left is a pure endgame.
+    let board: Board = Board::from_fen("4k3/pppp4/8/8/8/8/PPPP4/4K3 w - - 0 1").unwrap();
+    assert_eq!(board.psqt.phase(), 0);
+    assert_eq!(board.psqt.tapered(), board.psqt.eg(Color::White) - board.psqt.eg(Color::Black));
+}
+
+/// Checks that the accumulator kept by make and unmake matches one computed from scratch.
+fn check_accumulator(generator: &MoveGenerator, board: &mut Board, depth: u8) {
+    assert_eq!(board.psqt, Accumulator::from_board(board), "{}", board.to_fen());
+    assert_eq!(board.pawn_hash, board.compute_pawn_hash(), "{}", board.to_fen());
+    if depth == 0 {
+        return;
+    }
+
+    let mut moves: MoveList = MoveList::new();
+    let state: BoardTables = generator.gen_board_tables(board);
+    generator.gen_moves(&mut moves, board, &state);
+    for i in 0..moves.size() {
+        board.make(moves.at(i));
+        check_accumulator(generator, board, depth - 1);
+        board.unmake();
+    }
+    assert_eq!(board.psqt, Accumulator::from_board(board), "{}", board.to_fen());
+}
+
+#[test]
+fn test_accumulator_incremental() {
+    let generator: MoveGenerator = MoveGenerator::new();
+    for fen in &POSITIONS {
+        let mut board: Board = Board::from_fen(fen).unwrap();
+        check_accumulator(&generator, &mut board, 3);
+    }
+}
+
+#[test]
+fn test_pawn_structure() {
+    // Two isolated pawns on the same column, both passed.
+    let board: Board = Board::from_fen("4k3/8/8/8/8/P7/P7/4K3 w - - 0 1").unwrap();
+    let scores: pawns::PawnScores = pawns::evaluate_pawns(&board);
+    assert_eq!(scores.doubled[WHITE], pawns::DOUBLED);
+    assert_eq!(scores.isolated[WHITE], pawns::ISOLATED * 2);
+    assert_eq!(scores.passed[WHITE], pawns::PASSED[0] + pawns::PASSED[1]);
+    assert_eq!(scores.backward[WHITE], Score::default());
+
+    // The d3 pawn can't be defended by the c4 pawn and can't advance past the e5 pawn.
+    let board: Board = Board::from_fen("4k3/8/8/4p3/2P5/3P4/8/4K3 w - - 0 1").unwrap();
+    let scores: pawns::PawnScores = pawns::evaluate_pawns(&board);
+    assert_eq!(scores.backward[WHITE], pawns::BACKWARD);
+    assert_eq!(scores.isolated[WHITE], Score::default());
+    assert_eq!(scores.passed[WHITE], pawns::PASSED[2]);
+    assert_eq!(scores.isolated[BLACK], pawns::ISOLATED);
+    assert_eq!(scores.passed[BLACK], Score::default());
+
+    // Black pawns advance the other way.
+    let board: Board = Board::from_fen("4k3/8/8/8/8/1p6/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(pawns::evaluate_pawns(&board).passed[BLACK], pawns::PASSED[4]);
+}
+
+#[test]
+fn test_mobility() {
+    let centered: Board = Board::from_fen("4k3/8/8/8/4N3/8/8/4K3 w - - 0 1").unwrap();
+    let cornered: Board = Board::from_fen("4k3/8/8/8/8/8/8/N3K3 w - - 0 1").unwrap();
+    let knight: Score = mobility::MOBILITY[KNIGHT];
+    assert_eq!(mobility::evaluate_mobility(&centered, Color::White), knight * 4);
+    assert_eq!(mobility::evaluate_mobility(&cornered, Color::White), knight * -2);
+
+    // Squares attacked by enemy pawns don't count.
+    let guarded: Board = Board::from_fen("4k3/8/3p1p2/8/4N3/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(mobility::evaluate_mobility(&guarded, Color::White), knight * 2);
+}
+
+#[test]
+fn test_king_safety() {
+    let board: Board = Board::from_fen("6k1/8/8/8/8/8/5PPP/6K1 w - - 0 1").unwrap();
+    let safety: king::KingSafety = king::evaluate_king_safety(&board, Color::White);
+    assert_eq!(safety.shield, king::SHIELD_CLOSE * 3);
+    assert_eq!(safety.attacks, Score::default());
+    assert_eq!(king::evaluate_king_safety(&board, Color::Black).shield, Score::default());
+
+    // A queen and a rook bearing down on the king are worse than one of them alone.
+    let one: Board = Board::from_fen("6k1/8/8/8/8/6q1/8/6K1 w - - 0 1").unwrap();
+    let two: Board = Board::from_fen("6k1/8/8/8/8/6q1/8/r5K1 w - - 0 1").unwrap();
+    assert_eq!(king::evaluate_king_safety(&one, Color::White).attacks, Score::default());
+    assert!(king::evaluate_king_safety(&two, Color::White).attacks.mg < 0);
+}
+
+#[test]
+fn test_trace() {
+    let mut evaluator: Evaluator = Evaluator::new();
+    for fen in &POSITIONS {
+        let board: Board = Board::from_fen(fen).unwrap();
+        let trace: Trace = trace(&board);
+        assert_eq!(trace.score(board.turn), evaluate(&board));
+        // The cached pawn structure is the same as the one computed from scratch, both the
+        // first time and when it is found in the table.
+        assert_eq!(evaluator.trace(&board), trace);
+        assert_eq!(evaluator.trace(&board), trace);
+        assert_eq!(evaluator.evaluate(&board), evaluate(&board));
+    }
+
+    let text: String = trace(&Board::from_fen(START_FEN).unwrap()).to_string();
+    assert!(text.contains("Passed pawns"));
+    assert!(text.lines().any(|line| line.starts_with("Total") && line.ends_with(" 0")));
+}
@@ -0,0 +1,87 @@
+
+use crate::board::{self, *};
+use crate::generator::*;
+
+impl MoveGenerator {
+    /// Gets the pieces of the given color that attack a square. Only the pieces in the occupancy
+    /// are considered and only the occupancy blocks sliders, so pieces can be taken out of the
+    /// occupancy to see what attacks through them (e.g. when exchanging on a square).
+    pub fn attackers_to(&self, board: &Board, sq: Square, occupancy: u64, by: Color) -> u64 {
+        let sq: u8 = sq.index();
+        let pieces: &[u64; 6] = &board.bitboard.piece[by as usize];
+
+        // The pawns attacking the square are on the squares a pawn of the other color would attack.
+        let mut attackers: u64 = self.get_pawn_threat_mask(sq, by.opposite()) & pieces[board::PAWN];
+        attackers |= self.get_knight_move_mask(sq) & pieces[board::KNIGHT];
+        attackers |= self.get_bishop_move_mask(sq, occupancy)
+            & (pieces[board::BISHOP] | pieces[board::QUEEN]);
+        attackers |= self.get_rook_move_mask(sq, occupancy)
+            & (pieces[board::ROOK] | pieces[board::QUEEN]);
+        attackers |= self.get_king_move_mask(sq) & pieces[board::KING];
+
+        return attackers & occupancy;
+    }
+
+    /// Gets the pieces of both colors that attack a square, see attackers_to.
+    pub fn all_attackers_to(&self, board: &Board, sq: Square, occupancy: u64) -> u64 {
+        return self.attackers_to(board, sq, occupancy, Color::White)
+            | self.attackers_to(board, sq, occupancy, Color::Black);
+    }
+
+    /// Checks if any piece of the given color attacks a square in the current position.
+    pub fn is_attacked(&self, board: &Board, sq: Square, by: Color) -> bool {
+        return self.attackers_to(board, sq, board.bitboard.occupancy, by) != 0;
+    }
+
+    /// Checks if a legal move puts the enemy king in check, either directly or by uncovering an
+    /// attack from another piece. The board isn't changed.
+    pub fn gives_check(&self, board: &Board, mv: &Move) -> bool {
+        let from: u8 = mv.get_from();
+        let to: u8 = mv.get_to();
+        let flags: u16 = mv.get_flags();
+        let enemy_king: u64 = board.bitboard.piece[board.enemy_color() as usize][board::KING];
+        let king_sq: u8 = bitboard::peek_rbit(&enemy_king);
+
+        // Build our pieces and the occupancy as they will be after the move.
+        let mut pieces: [u64; 6] = board.bitboard.piece[board.turn as usize];
+        let moved: usize = board.type_at_sq(from) as usize;
+        let placed: usize = mv.promo_piece().map_or(moved, |ptype| ptype.index());
+        pieces[moved] &= !(1u64 << from);
+        pieces[placed] |= 1u64 << to;
+        let mut occupancy: u64 = (board.bitboard.occupancy & !(1u64 << from)) | (1u64 << to);
+
+        match flags {
+            ENPASSANT => {
+                let captured: u8 = if board.turn == Color::White { to + 8 } else { to - 8 };
+                occupancy &= !(1u64 << captured);
+            },
+            KING_SIDE_CASTLE | QUEEN_SIDE_CASTLE => {
+                use mailbox::*;
+                let (rook_from, rook_to): (u8, u8) = match (board.turn, flags) {
+                    (Color::White, KING_SIDE_CASTLE) => {
+                        (WHITE_KING_SIDE_ROOK_START, WHITE_KING_SIDE_ROOK_TARGET)
+                    },
+                    (Color::White, _) => (WHITE_QUEEN_SIDE_ROOK_START, WHITE_QUEEN_SIDE_ROOK_TARGET),
+                    (Color::Black, KING_SIDE_CASTLE) => {
+                        (BLACK_KING_SIDE_ROOK_START, BLACK_KING_SIDE_ROOK_TARGET)
+                    },
+                    (Color::Black, _) => (BLACK_QUEEN_SIDE_ROOK_START, BLACK_QUEEN_SIDE_ROOK_TARGET)
+                };
+                pieces[board::ROOK] ^= (1u64 << rook_from) | (1u64 << rook_to);
+                occupancy ^= (1u64 << rook_from) | (1u64 << rook_to);
+            },
+            _ => {}
+        }
+
+        // Kings never give check, so only the other pieces are looked at.
+        let mut checks: u64 = self.get_pawn_threat_mask(king_sq, board.enemy_color())
+            & pieces[board::PAWN];
+        checks |= self.get_knight_move_mask(king_sq) & pieces[board::KNIGHT];
+        checks |= self.get_bishop_move_mask(king_sq, occupancy)
+            & (pieces[board::BISHOP] | pieces[board::QUEEN]);
+        checks |= self.get_rook_move_mask(king_sq, occupancy)
+            & (pieces[board::ROOK] | pieces[board::QUEEN]);
+
+        return checks != 0;
+    }
+}
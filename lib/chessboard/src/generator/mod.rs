@@ -5,7 +5,13 @@ use crate::board::{self, *};
 use crate::moves::{*, MoveList};
 use std::fmt;
 
-mod tables;
+mod attacks;
+mod see;
+mod staged;
+pub(crate) mod tables;
+
+pub use see::SEE_VALUES;
+pub use staged::*;
 
 #[cfg(test)]
 mod tests;
@@ -17,6 +23,64 @@ pub struct BoardTables {
     pub pins: [u64; 9],
 }
 
+/// The state of a game, as seen by the rules of chess.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameStatus {
+    Ongoing,
+    /// Holds the color of the player that delivered the checkmate.
+    Checkmate(Color),
+    Stalemate,
+    InsufficientMaterial,
+    FiftyMove,
+    Repetition,
+    /// Holds the color of the player that ran out of time. Never returned by the generator, front
+    /// ends that keep a clock report it themselves.
+    Timeout(Color),
+    /// Holds the color of the player that resigned. Never returned by the generator.
+    Resign(Color),
+}
+
+impl GameStatus {
+    /// Returns true if the game has ended.
+    pub fn is_over(&self) -> bool {
+        return *self != GameStatus::Ongoing;
+    }
+
+    /// Gets the color of the winning player, if the game was won.
+    pub fn winner(&self) -> Option<Color> {
+        return match *self {
+            GameStatus::Checkmate(color) => Some(color),
+            GameStatus::Timeout(color) | GameStatus::Resign(color) => Some(color.opposite()),
+            _ => None
+        };
+    }
+
+    /// Gets the result of the game as written in a pgn file.
+    pub fn result(&self) -> &'static str {
+        return match self.winner() {
+            Some(Color::White) => "1-0",
+            Some(_) => "0-1",
+            None if self.is_over() => "1/2-1/2",
+            None => "*"
+        };
+    }
+}
+
+/// Selects which of the legal moves are generated. Searches look at the captures before the quiet
+/// moves, and only at the captures in quiescence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GenMode {
+    All,
+    /// Captures (including enpassant) and all promotions.
+    Captures,
+    /// Non-capturing moves other than promotions, including castles and double pawn pushes.
+    Quiets,
+    /// All the moves that get out of check. Only valid when the side to move is in check.
+    Evasions,
+    /// The moves that put the enemy king in check.
+    Checks,
+}
+
 pub struct MoveGenerator {
     tables: &'static tables::MoveTables
 }
@@ -38,26 +102,26 @@ impl fmt::Debug for BoardTables {
         // Print Pins
         writeln!(f, "PINS")?;
         for i in 0..9 as usize { write!(f, "---------------  ")?; }
-        writeln!(f, "")?;
+        writeln!(f)?;
         for j in 0..9 as usize {
             for i in 0..8 as usize {
                 write!(f, "{} ", pins[i][j])?;
             }
-            writeln!(f, "")?;
+            writeln!(f)?;
         }
-        write!(f, "\n")?;
+        writeln!(f)?;
 
         // Print Other Stuff
         writeln!(f, "PINS")?;
         for i in 0..3 as usize { write!(f, "---------------  ")?; }
-        writeln!(f, "")?;
+        writeln!(f)?;
         for j in 0..3 as usize {
             for i in 0..8 as usize {
                 write!(f, "{} ", boards[i][j])?;
             }
-            writeln!(f, "")?;
+            writeln!(f)?;
         }
-        write!(f, "\n")?;
+        writeln!(f)?;
 
         return Ok(());
     }
@@ -72,49 +136,237 @@ impl MoveGenerator {
     }
 
     pub fn gen_moves(&self, move_list: &mut MoveList, board: &Board, state: &BoardTables) {
+        self.gen_moves_mode(move_list, board, state, GenMode::All);
+    }
+
+    /// Generates the subset of the legal moves selected by the mode. The captures and the quiets
+    /// together are all of the legal moves.
+    pub fn gen_moves_mode(
+        &self,
+        move_list: &mut MoveList,
+        board: &Board,
+        state: &BoardTables,
+        mode: GenMode
+    ) {
         move_list.clear();
-        self.append_simple_moves(move_list, board, state);
-        self.append_castle_moves(move_list, board, state);
-        self.append_enp_moves(move_list, board, state);
-        self.append_double_pawn_push(move_list, board, state);
-        self.append_promos(move_list, board, state);
+        let own: u64 = board.bitboard.color[board.turn as usize];
+        let enemy: u64 = board.bitboard.color[board.enemy_color() as usize];
+
+        match mode {
+            GenMode::All => {
+                self.append_simple_moves(move_list, board, state, own, bitboard::FULL);
+                self.append_castle_moves(move_list, board, state);
+                self.append_enp_moves(move_list, board, state);
+                self.append_double_pawn_push(move_list, board, state);
+                self.append_promos(move_list, board, state);
+            },
+            GenMode::Captures => {
+                self.append_simple_moves(move_list, board, state, own, enemy);
+                self.append_enp_moves(move_list, board, state);
+                self.append_promos(move_list, board, state);
+            },
+            GenMode::Quiets => {
+                self.append_simple_moves(move_list, board, state, own, !board.bitboard.occupancy);
+                self.append_castle_moves(move_list, board, state);
+                self.append_double_pawn_push(move_list, board, state);
+            },
+            GenMode::Evasions => {
+                debug_assert!(state.checks != 0, "evasions generated while not in check");
+
+                // Only the king can get out of a double check.
+                let king: u64 = board.bitboard.piece[board.turn as usize][KING];
+                if bitboard::popcnt(&state.checks) > 1 {
+                    self.append_simple_moves(move_list, board, state, king, bitboard::FULL);
+                    return;
+                }
+
+                // Castling out of check is illegal, everything else has to block or capture.
+                self.append_simple_moves(move_list, board, state, own, bitboard::FULL);
+                self.append_enp_moves(move_list, board, state);
+                self.append_double_pawn_push(move_list, board, state);
+                self.append_promos(move_list, board, state);
+            },
+            GenMode::Checks => {
+                let mut moves: MoveList = MoveList::new();
+                self.gen_moves_mode(&mut moves, board, state, GenMode::All);
+                for i in 0..moves.size() {
+                    if self.gives_check(board, moves.at(i)) {
+                        move_list.push(moves.at(i).clone());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Decides whether the game is over in the given position. Checkmate and stalemate take
+    /// precedence over the draws that depend on the history, so a mate delivered on the last move
+    /// allowed by the 50-move rule still wins.
+    pub fn status(&self, board: &Board) -> GameStatus {
+        let mut moves: MoveList = MoveList::new();
+        let state: BoardTables = self.gen_board_tables(board);
+        self.gen_moves(&mut moves, board, &state);
+
+        if moves.size() == 0 {
+            return if state.checks != 0 {
+                GameStatus::Checkmate(board.enemy_color())
+            } else {
+                GameStatus::Stalemate
+            };
+        }
+        if board.is_insufficient_material() {
+            return GameStatus::InsufficientMaterial;
+        }
+        if board.is_fifty_move_draw() {
+            return GameStatus::FiftyMove;
+        }
+        if board.is_repetition(3) {
+            return GameStatus::Repetition;
+        }
+        return GameStatus::Ongoing;
+    }
+
+    /// Counts the leaf nodes of the legal move tree from the given position down to the given
+    /// depth. This is the standard way of validating a move generator against known results.
+    pub fn perft(&self, board: &mut Board, depth: u8) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let mut moves: MoveList = MoveList::new();
+        let state: BoardTables = self.gen_board_tables(board);
+        self.gen_moves(&mut moves, board, &state);
+
+        // Bulk count the last ply, there is no need to make moves just to count them.
+        if depth == 1 {
+            return moves.size() as u64;
+        }
+
+        let mut nodes: u64 = 0;
+        for i in 0..moves.size() {
+            board.make(moves.at(i));
+            nodes += self.perft(board, depth - 1);
+            board.unmake();
+        }
+        return nodes;
+    }
+
+    /// Runs a perft for each of the legal moves in the position, returning the node count below
+    /// each root move. Comparing these against another engine narrows down a failing perft.
+    pub fn divide(&self, board: &mut Board, depth: u8) -> Vec<(Move, u64)> {
+        let mut moves: MoveList = MoveList::new();
+        let state: BoardTables = self.gen_board_tables(board);
+        self.gen_moves(&mut moves, board, &state);
+
+        let mut counts: Vec<(Move, u64)> = Vec::with_capacity(moves.size());
+        for i in 0..moves.size() {
+            let mv: &Move = moves.at(i);
+            board.make(mv);
+            counts.push((mv.clone(), self.perft(board, depth.saturating_sub(1))));
+            board.unmake();
+        }
+        return counts;
+    }
+
+    /// Checks if a move, e.g. one that was received from a user, is legal in the position. Quiet
+    /// moves and captures are checked against the legal targets of the moving piece, the less
+    /// common kinds of move against the moves of their kind.
+    pub fn is_legal(&self, board: &Board, state: &BoardTables, mv: &Move) -> bool {
+        if !mv.has_valid_flags() {
+            return false;
+        }
+
+        let from: u8 = mv.get_from();
+        let to: u8 = mv.get_to();
+        let own: u64 = board.bitboard.color[board.turn as usize];
+        if from == to || own & (1u64 << from) == 0 {
+            return false;
+        }
+
+        let flags: u16 = mv.get_flags();
+        let mut moves: MoveList = MoveList::new();
+        match flags {
+            QUIET | CAPTURE => {
+                // Pawns moving to the last row have to promote.
+                let promo_sources: u64 = if board.turn == Color::White {
+                    bitboard::BLACK_PAWN_HOME
+                } else {
+                    bitboard::WHITE_PAWN_HOME
+                };
+                let pawns: u64 = board.bitboard.piece[board.turn as usize][PAWN];
+                if pawns & promo_sources & (1u64 << from) != 0 {
+                    return false;
+                }
+
+                if self.gen_legal_mv_mask(from, board, state) & (1u64 << to) == 0 {
+                    return false;
+                }
+                let is_capture: bool = board.bitboard.occupancy & (1u64 << to) != 0;
+                return is_capture == (flags == CAPTURE);
+            },
+            KING_SIDE_CASTLE | QUEEN_SIDE_CASTLE => {
+                self.append_castle_moves(&mut moves, board, state)
+            },
+            ENPASSANT => self.append_enp_moves(&mut moves, board, state),
+            DOUBLE_PAWN_PUSH => self.append_double_pawn_push(&mut moves, board, state),
+            _ => self.append_promos(&mut moves, board, state)
+        }
+
+        for i in 0..moves.size() {
+            if moves.at(i) == mv {
+                return true;
+            }
+        }
+        return false;
     }
 
     fn gen_legal_mv_mask(&self, sq: u8, board: &Board, state: &BoardTables) -> u64 {
-        // Generate the pseudo moves.
+        // Generate the pseudo moves. Pawns are the only piece that can't capture where they move.
         let piece_type: u8 = board.type_at_sq(sq);
-        let mut moves: u64 = self.gen_pseudo_move_mask(sq, piece_type,
-            board.bitboard.occupancy, board.turn);
-        moves &= !board.bitboard.color[WHITE];
-
-        // Adjust the moves for pins and checks.
-        moves &= if piece_type == KING as u8 { state.threats } else { !0x0 };
-        moves = MoveGenerator::pin_adjust(sq, moves, state);
-        moves &= state.check_blocks;
+        let mut moves: u64 = if piece_type as usize == PAWN {
+            let enemy: u64 = board.bitboard.color[board.enemy_color() as usize];
+            (self.get_pawn_threat_mask(sq, board.turn) & enemy)
+                | MoveGenerator::get_pawn_move_mask(sq, board.bitboard.occupancy, board.turn)
+        } else {
+            self.gen_pseudo_move_mask(sq, piece_type, board.bitboard.occupancy, board.turn)
+        };
+        moves &= !board.bitboard.color[board.turn as usize];
+
+        // Kings have to stay off of threatened squares, everything else has to respect pins and
+        // block or capture any checking pieces.
+        if piece_type as usize == KING {
+            moves &= !state.threats;
+        } else {
+            moves = MoveGenerator::pin_adjust(sq, moves, state);
+            moves &= state.check_blocks;
+        }
         
         return moves;
     }
 
+    /// Appends the quiet moves and captures of the given pieces that land on one of the targets.
     fn append_simple_moves(
         &self,
         move_list: &mut MoveList,
         board: &Board,
-        state: &BoardTables
+        state: &BoardTables,
+        pieces: u64,
+        targets: u64
     ) {
-        let mut pieces = board.bitboard.color[board.turn as usize];
+        let mut pieces = pieces;
 
         // Take out the pawns that are on the opposing sides home row as their next move will be
         // a promotion and should be handled separately.
-        pieces ^= board.bitboard.piece[board.turn as usize][PAWN] & if board.turn == WHITE as u8 {
+        let promo_row: u64 = if board.turn == Color::White {
             bitboard::BLACK_PAWN_HOME
         } else {
             bitboard::WHITE_PAWN_HOME
         };
+        pieces &= !(board.bitboard.piece[board.turn as usize][PAWN] & promo_row);
 
         // Append all of the moves to the list.
         while pieces != 0 {
             let sq = bitboard::pop_rbit(&mut pieces);
-            let mut simple = self.gen_legal_mv_mask(sq, board, state);
+            let mut simple = self.gen_legal_mv_mask(sq, board, state) & targets;
             while simple != 0 {
                 let target: u8 = bitboard::pop_rbit(&mut simple);
                 let flag: u16 = if (1u64 << target) & board.bitboard.occupancy == 0 {
@@ -122,7 +374,7 @@ impl MoveGenerator {
                 } else {
                     CAPTURE
                 };
-                move_list.push(Move::new(sq as u16, target as u16, flag))
+                move_list.push(Move::new_raw(sq as u16, target as u16, flag))
             }
         }
     }
@@ -145,7 +397,7 @@ impl MoveGenerator {
             } else {
                 mailbox::BLACK_KING_SIDE_CASTLE_TARGET
             };
-            move_list.push(Move::new(from as u16, to as u16, KING_SIDE_CASTLE));
+            move_list.push(Move::new_raw(from as u16, to as u16, KING_SIDE_CASTLE));
         }
 
         if MoveGenerator::qsc_legal(board, state) {
@@ -154,7 +406,7 @@ impl MoveGenerator {
             } else {
                 mailbox::BLACK_QUEEN_SIDE_CASTLE_TARGET
             };
-            move_list.push(Move::new(from as u16, to as u16, QUEEN_SIDE_CASTLE));
+            move_list.push(Move::new_raw(from as u16, to as u16, QUEEN_SIDE_CASTLE));
         }
     }
 
@@ -171,22 +423,22 @@ impl MoveGenerator {
         }
 
         // Get the column of that enpassanet.
-        let enemy_turn: usize = (!(board.turn as usize == WHITE)) as usize;
+        let enemy_turn: usize = board.enemy_color() as usize;
         let enp_row_start: u8 = if board.turn as usize == WHITE {
-            mailbox::WHITE_MIN_ENPASSANT_TARGET
-        } else {
             mailbox::BLACK_MIN_ENPASSANT_TARGET
+        } else {
+            mailbox::WHITE_MIN_ENPASSANT_TARGET
         };
         let enp_sq: u8 = enp_row_start + extra.get_enp_col();
         let enemy_sq: i8 = enp_sq as i8 + if board.turn as usize == WHITE { 8 } else { -8 };
 
         // Get all of the pieces that can enpassant.
-        let mut enp_sources = self.get_pawn_threat_mask(enemy_turn as u8, enp_sq)
+        let mut enp_sources = self.get_pawn_threat_mask(enp_sq, board.enemy_color())
             & board.bitboard.piece[board.turn as usize][PAWN];
 
         while enp_sources != 0 {
             let sq: u8 = bitboard::pop_rbit(&mut enp_sources);
-            let mv: Move = Move::new(sq as u16, enp_sq as u16, ENPASSANT);
+            let mv: Move = Move::new_raw(sq as u16, enp_sq as u16, ENPASSANT);
 
             // Update the occupancy mask to what it will be after the move happens.
             let mut new_occupancy: u64 = board.bitboard.occupancy;
@@ -194,17 +446,18 @@ impl MoveGenerator {
             new_occupancy &= !(1u64 << enemy_sq);
             new_occupancy |= 1u64 << enp_sq;
 
-            let king_sq: u8 = bitboard::peek_rbit(&board.bitboard.piece[enemy_turn][KING]);
+            let king_sq: u8 = bitboard::peek_rbit(&board.bitboard.piece[board.turn as usize][KING]);
 
-            // Check if any pawns are threatening the king after the move.
-            let pawn_threats: u64 = self.get_pawn_threat_mask(board.turn, king_sq)
-                & board.bitboard.piece[enemy_turn][PAWN];
+            // Check if any pawns are threatening the king after the move (the captured pawn is
+            // gone, so it can't be giving check anymore).
+            let pawn_threats: u64 = self.get_pawn_threat_mask(king_sq, board.turn)
+                & board.bitboard.piece[enemy_turn][PAWN] & !(1u64 << enemy_sq);
             if pawn_threats != 0 { continue; }
 
             // Check if any knights are threatening the king after the move.
-            let bishop_threats: u64 = self.get_knight_move_mask(king_sq)
+            let knight_threats: u64 = self.get_knight_move_mask(king_sq)
                 & board.bitboard.piece[enemy_turn][KNIGHT];
-            if pawn_threats != 0 { continue; }
+            if knight_threats != 0 { continue; }
 
             // Check if any bishops or queens threaten the king after the move (handles pin).
             let bishop_threats: u64 = self.get_bishop_move_mask(king_sq, new_occupancy)
@@ -213,8 +466,8 @@ impl MoveGenerator {
             if bishop_threats != 0 { continue; }
 
             // Check if any rooks or queens threaten the king after the move (handles pin).
-            let rook_threats: u64 = self.get_bishop_move_mask(king_sq, new_occupancy)
-                & (board.bitboard.piece[enemy_turn][BISHOP]
+            let rook_threats: u64 = self.get_rook_move_mask(king_sq, new_occupancy)
+                & (board.bitboard.piece[enemy_turn][ROOK]
                     | board.bitboard.piece[enemy_turn][QUEEN]);
             if rook_threats != 0 { continue; }
 
@@ -247,7 +500,7 @@ impl MoveGenerator {
                 target = MoveGenerator::pin_adjust(from as u8, target, state);
                 target &= state.check_blocks;
                 if target != 0 {
-                    move_list.push(Move::new(from as u16, to as u16, DOUBLE_PAWN_PUSH));
+                    move_list.push(Move::new_raw(from as u16, to as u16, DOUBLE_PAWN_PUSH));
                 }
             }
         }
@@ -265,31 +518,32 @@ impl MoveGenerator {
             bitboard::WHITE_PAWN_HOME
         };
         let mut pawns: u64 = board.bitboard.piece[board.turn as usize][PAWN] & promo_row;
-        let direction: i8 = if board.turn as usize == WHITE { 1 } else { -1 };
-        let enemy_turn: usize = (!(board.turn as usize == WHITE)) as usize;
+        let direction: i8 = if board.turn as usize == WHITE { -1 } else { 1 };
+        let enemy_turn: usize = board.enemy_color() as usize;
 
         while pawns != 0 {
             let sq: u8 = bitboard::pop_rbit(&mut pawns);
-            let mut legal_mask: u64 = MoveGenerator::get_pin_mask(sq, state);
+            let mut legal_mask: u64 = MoveGenerator::pin_adjust(sq, bitboard::FULL, state);
             legal_mask &= state.check_blocks;
 
             let push_target: i8 = sq as i8 + 8 * direction;
-            if board.type_at_sq(sq) as usize == EMPTY && (1u64 << push_target) & legal_mask != 0 {
-                move_list.push(Move::new(sq as u16, push_target as u16, KNIGHT_PROMO));
-                move_list.push(Move::new(sq as u16, push_target as u16, BISHOP_PROMO));
-                move_list.push(Move::new(sq as u16, push_target as u16, ROOK_PROMO));
-                move_list.push(Move::new(sq as u16, push_target as u16, QUEEN_PROMO));
+            if board.type_at_sq(push_target as u8) as usize == EMPTY
+                && (1u64 << push_target) & legal_mask != 0 {
+                move_list.push(Move::new_raw(sq as u16, push_target as u16, KNIGHT_PROMO));
+                move_list.push(Move::new_raw(sq as u16, push_target as u16, BISHOP_PROMO));
+                move_list.push(Move::new_raw(sq as u16, push_target as u16, ROOK_PROMO));
+                move_list.push(Move::new_raw(sq as u16, push_target as u16, QUEEN_PROMO));
             }
 
-            let mut cap_targets: u64 = self.get_pawn_threat_mask(board.turn, sq);
+            let mut cap_targets: u64 = self.get_pawn_threat_mask(sq, board.turn);
             cap_targets &= board.bitboard.color[enemy_turn];
             cap_targets &= legal_mask;
             while cap_targets != 0 {
                 let target: u8 = bitboard::pop_rbit(&mut cap_targets);
-                move_list.push(Move::new(sq as u16, target as u16, KNIGHT_PROMO_CAPTURE));
-                move_list.push(Move::new(sq as u16, target as u16, KNIGHT_PROMO_CAPTURE));
-                move_list.push(Move::new(sq as u16, target as u16, ROOK_PROMO_CAPTURE));
-                move_list.push(Move::new(sq as u16, target as u16, QUEEN_PROMO_CAPTURE));
+                move_list.push(Move::new_raw(sq as u16, target as u16, KNIGHT_PROMO_CAPTURE));
+                move_list.push(Move::new_raw(sq as u16, target as u16, BISHOP_PROMO_CAPTURE));
+                move_list.push(Move::new_raw(sq as u16, target as u16, ROOK_PROMO_CAPTURE));
+                move_list.push(Move::new_raw(sq as u16, target as u16, QUEEN_PROMO_CAPTURE));
             }
         }
     }
@@ -308,12 +562,13 @@ impl MoveGenerator {
         }
     }
 
-    fn get_pawn_threat_mask(&self, sq: u8, turn: u8) -> u64 {
+    fn get_pawn_threat_mask(&self, sq: u8, turn: Color) -> u64 {
         self.tables.read_pawn_attacks(sq, turn)
     }
 
-    fn get_pawn_move_mask(sq: u8, occupancy: u64) -> u64 {
-        (1u64 << sq) & !occupancy
+    fn get_pawn_move_mask(sq: u8, occupancy: u64, turn: Color) -> u64 {
+        let target: u8 = if turn as usize == WHITE { sq - 8 } else { sq + 8 };
+        (1u64 << target) & !occupancy
     }
 
     fn get_knight_move_mask(&self, sq: u8) -> u64 {
@@ -337,7 +592,7 @@ impl MoveGenerator {
         self.tables.read_king_attacks(sq)
     }
 
-    fn gen_pseudo_move_mask(&self, sq: u8, piece_type: u8, occupancy: u64, turn: u8) -> u64 {
+    fn gen_pseudo_move_mask(&self, sq: u8, piece_type: u8, occupancy: u64, turn: Color) -> u64 {
         match piece_type as usize {
             board::PAWN   => self.get_pawn_threat_mask(sq, turn),
             board::KNIGHT => self.get_knight_move_mask(sq),
@@ -351,18 +606,18 @@ impl MoveGenerator {
 
     fn gen_threats(&self, board: &Board) -> u64 {
         let mut threats: u64;
-        let not_turn: u8 = (board.turn != 0) as u8;
+        let not_turn: Color = board.enemy_color();
 
-        // Smear the pawns to get all of their attacks.
-        let pawns: u64 = board.bitboard.piece[board.turn as usize][board::PAWN as usize];
+        // Smear the enemy pawns to get all of their attacks.
+        let pawns: u64 = board.bitboard.piece[not_turn as usize][board::PAWN as usize];
         let king: u64 = board.bitboard.piece[board.turn as usize][board::KING as usize];
 
-        threats = bitboard::pawn_smear(pawns, board.turn == board::WHITE as u8);
+        threats = bitboard::pawn_smear(pawns, not_turn == Color::White);
 
         // For each of the remaining pieces, gen the legal moves after removing the desired king
         // from the occupancy mask. This is because the king cannot block a threat, pieces should
         // "see through" the king).
-        let mut pieces: u64 = board.bitboard.color[board.turn as usize] ^ pawns;
+        let mut pieces: u64 = board.bitboard.color[not_turn as usize] ^ pawns;
         let occupancy: u64 = board.bitboard.occupancy ^ king;
         while pieces != 0 {
             let sq: u8 = bitboard::pop_rbit(&mut pieces);
@@ -378,18 +633,19 @@ impl MoveGenerator {
     }
 
     fn gen_checks(&self, board: &Board, threats: u64) -> u64 {
-        let not_turn: u8 = (board.turn != 0) as u8;
+        let not_turn: Color = board.enemy_color();
         let pieces: &[u64; 6] = &board.bitboard.piece[not_turn as usize];
+        let king: u64 = board.bitboard.piece[board.turn as usize][board::KING];
         let occupancy: u64 = board.bitboard.occupancy;
 
         // Exit early if the king isn't on the threat squares.
-        if pieces[board::KING] & threats == 0 {
+        if king & threats == 0 {
             return 0;
         }
 
         // Build the list of pieces that are checking the king.
-        let king_sq: u8 = bitboard::peek_rbit(&pieces[board::KING]);
-        let mut checks = self.get_pawn_threat_mask(king_sq, not_turn) & pieces[board::PAWN];
+        let king_sq: u8 = bitboard::peek_rbit(&king);
+        let mut checks = self.get_pawn_threat_mask(king_sq, board.turn) & pieces[board::PAWN];
         checks |= self.get_knight_move_mask(king_sq) & pieces[board::KNIGHT];
         checks |= self.get_bishop_move_mask(king_sq, occupancy)
             & (pieces[board::BISHOP] | pieces[board::QUEEN]);
@@ -422,11 +678,9 @@ impl MoveGenerator {
         let occupancy: u64 = board.bitboard.occupancy;
         let blockers: u64 = board.bitboard.color[board.turn as usize];
 
-        // TODO: REMOVE ME
         let mut pinner: u64 = self.xray_rook_attacks(occupancy, blockers, king_sq)
             & (board.bitboard.piece[board.enemy_color() as usize][ROOK]
                 | board.bitboard.piece[board.enemy_color() as usize][QUEEN]);
-        println!("");
         while pinner != 0 {
             let sq: u8 = bitboard::pop_rbit(&mut pinner);
             pins[i] = self.tables.read_to_from_table(king_sq, sq);
@@ -437,8 +691,6 @@ impl MoveGenerator {
         let mut pinner: u64 = self.xray_bishop_attacks(occupancy, blockers, king_sq)
             & (board.bitboard.piece[board.enemy_color() as usize][BISHOP]
                 | board.bitboard.piece[board.enemy_color() as usize][QUEEN]);
-        println!("");
-        i = 0;
         while pinner != 0 {
             let sq: u8 = bitboard::pop_rbit(&mut pinner);
             pins[i] = self.tables.read_to_from_table(king_sq, sq);
@@ -457,9 +709,9 @@ impl MoveGenerator {
     }
 
     fn xray_bishop_attacks(&self, occupancy: u64, mut blockers: u64, sq: u8) -> u64 {
-        let attacks = self.tables.read_rook_attacks(sq, occupancy);
+        let attacks = self.tables.read_bishop_attacks(sq, occupancy);
         blockers &= attacks;
-        return attacks ^ self.tables.read_rook_attacks(sq, occupancy ^ blockers);
+        return attacks ^ self.tables.read_bishop_attacks(sq, occupancy ^ blockers);
     }
     
     fn get_pin_mask(sq: u8, state: &BoardTables) -> u64 {
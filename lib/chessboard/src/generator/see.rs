@@ -0,0 +1,164 @@
+
+use crate::board::{self, *};
+use crate::generator::*;
+
+/// Piece values used by the static exchange evaluation, indexed by piece type. The king is worth
+/// more than everything else together so that it is never traded.
+pub const SEE_VALUES: [i32; 6] = [ 100, 300, 300, 500, 900, 20000 ];
+
+impl MoveGenerator {
+    /// Gets the material won on the target square by the move before the exchange starts, and the
+    /// value of the piece that is left standing on it.
+    fn see_first_capture(board: &Board, mv: &Move) -> (i32, i32) {
+        let mut gain: i32 = match mv.get_flags() {
+            ENPASSANT => SEE_VALUES[board::PAWN],
+            _ if mv.is_capture() => SEE_VALUES[board.type_at_sq(mv.get_to()) as usize],
+            _ => 0
+        };
+        let mut piece: i32 = SEE_VALUES[board.type_at_sq(mv.get_from()) as usize];
+        if let Some(ptype) = mv.promo_piece() {
+            gain += SEE_VALUES[ptype.index()] - SEE_VALUES[board::PAWN];
+            piece = SEE_VALUES[ptype.index()];
+        }
+        return (gain, piece);
+    }
+
+    /// Gets the occupancy once the move is made, without the piece on the target square (it
+    /// doesn't take part in the exchange as an attacker).
+    fn see_occupancy(board: &Board, mv: &Move) -> u64 {
+        let to: u8 = mv.get_to();
+        let mut occupancy: u64 = board.bitboard.occupancy;
+        occupancy &= !(1u64 << mv.get_from()) & !(1u64 << to);
+        if mv.get_flags() == ENPASSANT {
+            let captured: u8 = if board.turn == Color::White { to + 8 } else { to - 8 };
+            occupancy &= !(1u64 << captured);
+        }
+        return occupancy;
+    }
+
+    /// Finds the least valuable piece of a color in a set of attackers.
+    fn least_valuable(board: &Board, attackers: u64, color: Color) -> Option<(usize, u64)> {
+        for ptype in board::PAWN..=board::KING {
+            let pieces: u64 = attackers & board.bitboard.piece[color as usize][ptype];
+            if pieces != 0 {
+                return Some((ptype, pieces & pieces.wrapping_neg()));
+            }
+        }
+        return None;
+    }
+
+    /// Adds the sliders that attack the target square through the square that was just vacated.
+    fn see_xrays(&self, board: &Board, to: u8, ptype: usize, occupancy: u64) -> u64 {
+        let pieces = |ptype: usize| {
+            board.bitboard.piece[WHITE][ptype] | board.bitboard.piece[BLACK][ptype]
+        };
+        let diagonal: u64 = pieces(board::BISHOP) | pieces(board::QUEEN);
+        let straight: u64 = pieces(board::ROOK) | pieces(board::QUEEN);
+
+        let mut xrays: u64 = 0;
+        if ptype == board::PAWN || ptype == board::BISHOP || ptype == board::QUEEN {
+            xrays |= self.tables.read_bishop_attacks(to, occupancy) & diagonal;
+        }
+        if ptype == board::ROOK || ptype == board::QUEEN {
+            xrays |= self.tables.read_rook_attacks(to, occupancy) & straight;
+        }
+        return xrays & occupancy;
+    }
+
+    /// Statically evaluates the exchange of pieces started by a move on its target square. Both
+    /// sides recapture with their least valuable piece and may stop whenever continuing would
+    /// lose material. Pins and checks are ignored, recapturing pawns promote to queens. Returns
+    /// the material won by the side to move.
+    pub fn see(&self, board: &Board, mv: &Move) -> i32 {
+        let to: u8 = mv.get_to();
+        let target: Square = Square::from_raw(to);
+        let (first_gain, first_piece): (i32, i32) = MoveGenerator::see_first_capture(board, mv);
+
+        let mut gain: [i32; 32] = [0; 32];
+        gain[0] = first_gain;
+        let mut on_square: i32 = first_piece;
+        let mut occupancy: u64 = MoveGenerator::see_occupancy(board, mv);
+        let mut attackers: u64 = self.all_attackers_to(board, target, occupancy);
+        let mut side: Color = board.enemy_color();
+        let mut depth: usize = 0;
+        let promo_row: u64 = bitboard::TOP_ROW | bitboard::BOTTOM_ROW;
+
+        // Play out the captures, recording the balance after each one.
+        while let Some((ptype, attacker)) = MoveGenerator::least_valuable(board, attackers, side) {
+            depth += 1;
+            gain[depth] = on_square - gain[depth - 1];
+            on_square = SEE_VALUES[ptype];
+
+            // Pawns recapturing on the last row promote to a queen.
+            if ptype == board::PAWN && promo_row & (1u64 << to) != 0 {
+                gain[depth] += SEE_VALUES[board::QUEEN] - SEE_VALUES[board::PAWN];
+                on_square = SEE_VALUES[board::QUEEN];
+            }
+
+            occupancy ^= attacker;
+            attackers = (attackers & occupancy) | self.see_xrays(board, to, ptype, occupancy);
+            side = side.opposite();
+        }
+
+        // Each side can stop the exchange instead of capturing.
+        while depth > 0 {
+            gain[depth - 1] = -(-gain[depth - 1]).max(gain[depth]);
+            depth -= 1;
+        }
+        return gain[0];
+    }
+
+    /// Checks if the static exchange evaluation of a move is at least the threshold. This stops as
+    /// soon as the outcome is known, so it is cheaper than comparing the result of see.
+    pub fn see_ge(&self, board: &Board, mv: &Move, threshold: i32) -> bool {
+        let to: u8 = mv.get_to();
+        let target: Square = Square::from_raw(to);
+        let (first_gain, first_piece): (i32, i32) = MoveGenerator::see_first_capture(board, mv);
+
+        // The swap below doesn't model pawns promoting while recapturing.
+        if (bitboard::TOP_ROW | bitboard::BOTTOM_ROW) & (1u64 << to) != 0 {
+            return self.see(board, mv) >= threshold;
+        }
+
+        // Even if the piece is lost for nothing in return, the move may be good enough.
+        let mut swap: i32 = first_gain - threshold;
+        if swap < 0 {
+            return false;
+        }
+        swap = first_piece - swap;
+        if swap <= 0 {
+            return true;
+        }
+
+        let mut occupancy: u64 = MoveGenerator::see_occupancy(board, mv);
+        let mut attackers: u64 = self.all_attackers_to(board, target, occupancy);
+        let mut side: Color = board.turn;
+        // The result if the side to move in the exchange runs out of attackers.
+        let mut result: bool = true;
+
+        loop {
+            side = side.opposite();
+            attackers &= occupancy;
+            let least: Option<(usize, u64)> = MoveGenerator::least_valuable(board, attackers, side);
+            let (ptype, attacker): (usize, u64) = match least {
+                Some(least) => least,
+                None => break
+            };
+            result = !result;
+
+            // A king can only capture if the other side has nothing left to recapture with.
+            if ptype == board::KING {
+                let others: u64 = attackers & board.bitboard.color[side.opposite() as usize];
+                return if others != 0 { !result } else { result };
+            }
+
+            swap = SEE_VALUES[ptype] - swap;
+            if swap < result as i32 {
+                break;
+            }
+            occupancy ^= attacker;
+            attackers |= self.see_xrays(board, to, ptype, occupancy);
+        }
+        return result;
+    }
+}
@@ -0,0 +1,158 @@
+
+use crate::generator::*;
+use crate::ordering::*;
+
+/// The stages of a StagedMoves, in the order they are visited.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Stage {
+    HashMove,
+    GenCaptures,
+    Captures,
+    Killers,
+    GenQuiets,
+    Quiets,
+    GenEvasions,
+    Evasions,
+    Done,
+}
+
+/// Hands out the legal moves of a position one stage at a time: the hash move, the captures, the
+/// killer moves and then the quiet moves. Later stages are only generated once the earlier ones
+/// are used up, so a search that cuts off early never generates the quiet moves. When in check
+/// the evasions are generated in one go after the hash move.
+///
+/// Within a stage the moves come out best first. Captures are ordered by MVV-LVA, and quiet moves
+/// by the score the caller gives them. Evasions put the captures before the quiet moves.
+///
+/// The board is passed to every call of next_move instead of being borrowed so that the search can
+/// make and unmake the moves in between. It must be in the same position on every call.
+pub struct StagedMoves {
+    generator: MoveGenerator,
+    state: BoardTables,
+    stage: Stage,
+    hash_move: Option<Move>,
+    killers: [Option<Move>; 2],
+    moves: ScoredMoves,
+    /// The number of killers that were looked at in the killer stage.
+    index: usize
+}
+
+impl StagedMoves {
+    /// Creates the staged moves for a position. The hash and killer moves may come from other
+    /// positions, they are checked for legality before they are returned.
+    pub fn new(board: &Board, hash_move: Option<Move>, killers: [Option<Move>; 2]) -> Self {
+        let generator: MoveGenerator = MoveGenerator::new();
+        let state: BoardTables = generator.gen_board_tables(board);
+        return StagedMoves {
+            generator,
+            state,
+            stage: Stage::HashMove,
+            hash_move,
+            killers,
+            moves: ScoredMoves::new(MoveList::new(), |_| 0),
+            index: 0
+        };
+    }
+
+    /// Gets the tables of the position, so that they don't have to be generated twice.
+    pub fn state(&self) -> &BoardTables {
+        return &self.state;
+    }
+
+    /// Returns true if the move was already returned by an earlier stage.
+    fn returned_early(&self, mv: &Move) -> bool {
+        if self.hash_move.as_ref() == Some(mv) {
+            return true;
+        }
+        // Killers are only returned in the killer stage, which isn't visited in check.
+        return self.stage == Stage::Quiets
+            && self.killers.iter().any(|killer| killer.as_ref() == Some(mv));
+    }
+
+    /// Returns the best move left in the list of the current stage that wasn't already returned.
+    fn next_listed(&mut self) -> Option<Move> {
+        while let Some((mv, _)) = self.moves.pick_next() {
+            if !self.returned_early(&mv) {
+                return Some(mv);
+            }
+        }
+        return None;
+    }
+
+    fn generate(&mut self, board: &Board, mode: GenMode, score: impl FnMut(&Move) -> i32) {
+        let mut moves: MoveList = MoveList::new();
+        self.generator.gen_moves_mode(&mut moves, board, &self.state, mode);
+        self.moves = ScoredMoves::new(moves, score);
+    }
+
+    /// Gets the next legal move, or None once all of them have been returned. Quiet moves are
+    /// ordered by quiet_score, which is only called when they are generated.
+    pub fn next_move(&mut self, board: &Board, quiet_score: impl Fn(&Move) -> i32) -> Option<Move> {
+        loop {
+            match self.stage {
+                Stage::HashMove => {
+                    self.stage = if self.state.checks != 0 {
+                        Stage::GenEvasions
+                    } else {
+                        Stage::GenCaptures
+                    };
+                    match &self.hash_move {
+                        Some(mv) if self.generator.is_legal(board, &self.state, mv) => {
+                            return Some(mv.clone());
+                        },
+                        _ => self.hash_move = None
+                    }
+                },
+                Stage::GenCaptures => {
+                    self.generate(board, GenMode::Captures, |mv| mvv_lva(board, mv));
+                    self.stage = Stage::Captures;
+                },
+                Stage::Captures => {
+                    match self.next_listed() {
+                        Some(mv) => return Some(mv),
+                        None => self.stage = Stage::Killers
+                    }
+                },
+                Stage::Killers => {
+                    // Killers that are captures were already returned with the captures.
+                    while self.index < self.killers.len() {
+                        let killer: Option<Move> = self.killers[self.index].clone();
+                        self.index += 1;
+                        if let Some(mv) = killer {
+                            let quiet: bool = !mv.is_capture() && !mv.is_promotion();
+                            let repeated: bool = self.index == 2
+                                && self.killers[0].as_ref() == Some(&mv);
+                            if quiet && !repeated && self.hash_move.as_ref() != Some(&mv)
+                                && self.generator.is_legal(board, &self.state, &mv) {
+                                return Some(mv);
+                            }
+                        }
+                    }
+                    self.stage = Stage::GenQuiets;
+                },
+                Stage::GenQuiets => {
+                    self.generate(board, GenMode::Quiets, &quiet_score);
+                    self.stage = Stage::Quiets;
+                },
+                Stage::GenEvasions => {
+                    self.generate(board, GenMode::Evasions, |mv| {
+                        if mv.is_capture() || mv.is_promotion() {
+                            CAPTURE_SCORE + mvv_lva(board, mv)
+                        } else {
+                            quiet_score(mv)
+                        }
+                    });
+                    self.stage = Stage::Evasions;
+                },
+                Stage::Quiets | Stage::Evasions => {
+                    let next: Option<Move> = self.next_listed();
+                    if next.is_none() {
+                        self.stage = Stage::Done;
+                    }
+                    return next;
+                },
+                Stage::Done => return None
+            }
+        }
+    }
+}
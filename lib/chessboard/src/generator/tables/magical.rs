@@ -177,7 +177,7 @@ pub fn gen_rook_attack_table() -> [MagicalTable; 64] {
 }
 
 pub fn read_magical_table(table: &MagicalTable, occupancy: u64) -> u64 {
-    let key: u16 = get_key(&table, occupancy);
+    let key: u16 = get_key(table, occupancy);
     return table.data[key as usize];
 }
 
@@ -196,28 +196,28 @@ fn get_rook_occ_mask(sq: u8) -> u64 {
     // Down
     let mut rank = source_rank + 1;
     while rank <= 6 {
-        result |= 1u64 << source_file + rank * 8;
+        result |= 1u64 << (source_file + rank * 8);
         rank += 1;
     }
 
     // Up
     let mut rank = source_rank - 1;
     while rank >= 1 {
-        result |= 1u64 << source_file + rank * 8;
+        result |= 1u64 << (source_file + rank * 8);
         rank -= 1;
     }
 
     // Right
     let mut file = source_file + 1;
     while file <= 6 {
-        result |= 1u64 << file + source_rank * 8;
+        result |= 1u64 << (file + source_rank * 8);
         file += 1;
     }
 
     // Left
     let mut file = source_file - 1;
     while file >= 1 {
-        result |= 1u64 << file + source_rank * 8;
+        result |= 1u64 << (file + source_rank * 8);
         file -= 1;
     }
 
@@ -233,7 +233,7 @@ fn get_bishop_occ_mask(sq: u8) -> u64 {
     let mut rank = source_rank + 1;
     let mut file = source_file + 1;
     while rank <= 6 && file <= 6 {
-        result |= 1u64 << file + rank * 8;
+        result |= 1u64 << (file + rank * 8);
         rank += 1;
         file += 1;
     }
@@ -242,7 +242,7 @@ fn get_bishop_occ_mask(sq: u8) -> u64 {
     let mut rank = source_rank + 1;
     let mut file = source_file - 1;
     while rank <= 6 && file >= 1 {
-        result |= 1u64 << file + rank * 8;
+        result |= 1u64 << (file + rank * 8);
         rank += 1;
         file -= 1;
     }
@@ -251,7 +251,7 @@ fn get_bishop_occ_mask(sq: u8) -> u64 {
     let mut rank = source_rank - 1;
     let mut file = source_file + 1;
     while rank >= 1 && file <= 6 {
-        result |= 1u64 << file + rank * 8;
+        result |= 1u64 << (file + rank * 8);
         rank -= 1;
         file += 1;
     }
@@ -260,7 +260,7 @@ fn get_bishop_occ_mask(sq: u8) -> u64 {
     let mut rank = source_rank - 1;
     let mut file = source_file - 1;
     while rank >= 1 && file >= 1 {
-        result |= 1u64 << file + rank * 8;
+        result |= 1u64 << (file + rank * 8);
         rank -= 1;
         file -= 1;
     }
@@ -420,9 +420,9 @@ fn gen_table(is_bishop: bool) -> [MagicalTable; 64] {
                 println!("IDX: {}", j);
                 println!("KEY: {}", key);
                 bitboard::print(legal_recalc);
-                println!("");
+                println!();
                 bitboard::print(table.data[key as usize]);
-                println!("");
+                println!();
                 bitboard::print(occupied_squares[j]);
                 panic!();
             }
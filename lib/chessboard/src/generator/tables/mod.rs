@@ -1,4 +1,5 @@
 
+use crate::types::*;
 use std::sync::OnceLock;
 
 mod normal;
@@ -19,21 +20,21 @@ pub struct MoveTables {
 impl MoveTables {
     pub fn get_instance() -> &'static Self {
         static TABLES: OnceLock<MoveTables> = OnceLock::new();
-        TABLES.get_or_init(|| MoveTables::new())
+        TABLES.get_or_init(MoveTables::new)
     }
 
     /// Reads the knight attack table. Must not be passed in a square value greater than 64.
-    pub fn read_pawn_attacks(&self, sq: u8, turn: u8) -> u64 {
+    pub(crate) fn read_pawn_attacks(&self, sq: u8, turn: Color) -> u64 {
         return self.pawn_attacks[turn as usize][sq as usize];
     }
 
     /// Reads the knight attack table. Must not be passed in a square value greater than 64.
-    pub fn read_knight_attacks(&self, sq: u8) -> u64 {
+    pub(crate) fn read_knight_attacks(&self, sq: u8) -> u64 {
         return self.knight_attacks[sq as usize];
     }
 
     /// Reads the bishop attack table. Must not be passed in a square value greater than 64.
-    pub fn read_bishop_attacks(&self, sq: u8, occupancy: u64) -> u64 {
+    pub(crate) fn read_bishop_attacks(&self, sq: u8, occupancy: u64) -> u64 {
         return magical::read_magical_table(
             &self.bishop_attacks[sq as usize],
             occupancy
@@ -41,7 +42,7 @@ impl MoveTables {
     }
 
     /// Reads the rook attack table. Must not be passed in a square value greater than 64.
-    pub fn read_rook_attacks(&self, sq: u8, occupancy: u64) -> u64 {
+    pub(crate) fn read_rook_attacks(&self, sq: u8, occupancy: u64) -> u64 {
         return magical::read_magical_table(
             &self.rook_attacks[sq as usize],
             occupancy
@@ -49,11 +50,11 @@ impl MoveTables {
     }
 
     /// Reads the king attack table. Must not be passed in a square value greater than 64.
-    pub fn read_king_attacks(&self, sq: u8) -> u64 {
+    pub(crate) fn read_king_attacks(&self, sq: u8) -> u64 {
         return self.king_attacks[sq as usize];
     }
 
-    pub fn read_to_from_table(&self, sq1: u8, sq2: u8) -> u64 {
+    pub(crate) fn read_to_from_table(&self, sq1: u8, sq2: u8) -> u64 {
         return self.to_from_table[sq1 as usize][sq2 as usize];
     }
 }
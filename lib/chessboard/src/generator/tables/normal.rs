@@ -34,35 +34,38 @@ pub fn gen_to_from_table() -> [[u64; 64]; 64] {
     return table;
 }
 
+/// Gets the ray of squares connecting two squares (including both ends) if they share a row,
+/// column or diagonal. Returns an empty mask otherwise.
 fn get_connecting_ray(sq1: u8, sq2: u8) -> u64 {
-    let sq1_rank: u8 = sq1 % 8;
-    let sq1_file: u8 = sq1 / 8;
-    let sq2_rank: u8 = sq1 % 8;
-    let sq2_file: u8 = sq1 / 8;
+    let sq1_row: i8 = (sq1 / 8) as i8;
+    let sq1_col: i8 = (sq1 % 8) as i8;
+    let sq2_row: i8 = (sq2 / 8) as i8;
+    let sq2_col: i8 = (sq2 % 8) as i8;
 
     if sq1 == sq2 {
         return 1u64 << sq1;
     }
 
-    let mut sq: i8 = sq1.try_into().unwrap();
-    let mut mask: u64 = 0;
-    let direction: i8 = if sq1_rank == sq2_rank {
-        if sq1 < sq2 { 1 } else { -1 }
-    } else if sq1_file == sq2_file {
-        if sq1 < sq2 { 8 } else { -8 }
-    } else if sq1_file + sq1_rank == sq2_file + sq2_rank {
-        if sq1 < sq2 { 7 } else { -7 }
-    } else if sq1_file - sq1_rank == sq2_file - sq2_rank {
-        if sq1 < sq2 { 9 } else { -9 }
+    let step: i8 = if sq1 < sq2 { 1 } else { -1 };
+    let direction: i8 = if sq1_row == sq2_row {
+        step
+    } else if sq1_col == sq2_col {
+        8 * step
+    } else if sq1_row + sq1_col == sq2_row + sq2_col {
+        7 * step
+    } else if sq1_row - sq1_col == sq2_row - sq2_col {
+        9 * step
     } else {
         return 0;
     };
 
-    while <i8 as TryInto<u8>>::try_into(sq).unwrap() != sq2 {
+    let mut sq: i8 = sq1 as i8;
+    let mut mask: u64 = 0;
+    while sq != sq2 as i8 {
         mask |= 1u64 << sq;
         sq += direction;
     }
-    mask |= 1u64 << sq1;
+    mask |= 1u64 << sq2;
 
     return mask;
 }
@@ -74,7 +77,7 @@ fn gen_table_from_offsets(offsets: Vec<i8>) -> [u64; 64] {
         for j in 0..offsets.len() {
             let sq: i8 = i + offsets[j];
             
-            if sq < 0 || sq >= 64 {
+            if !(0..64).contains(&sq) {
                 continue;
             }
 
@@ -5,11 +5,11 @@ use crate::generator::tables::*;
 #[test]
 fn test_pawn_attacks() {
     let tables: &MoveTables = MoveTables::get_instance();
-    assert_eq!(MoveTables::read_pawn_attacks(tables,  1, WHITE as u8), 0x0000000000000000u64);
-    assert_eq!(MoveTables::read_pawn_attacks(tables,  9, BLACK as u8), 0x0000000000050000u64);
-    assert_eq!(MoveTables::read_pawn_attacks(tables, 24, BLACK as u8), 0x0000000200000000u64);
-    assert_eq!(MoveTables::read_pawn_attacks(tables, 31, BLACK as u8), 0x0000004000000000u64);
-    assert_eq!(MoveTables::read_pawn_attacks(tables, 47, WHITE as u8), 0x0000004000000000u64);
+    assert_eq!(MoveTables::read_pawn_attacks(tables,  1, Color::White), 0x0000000000000000u64);
+    assert_eq!(MoveTables::read_pawn_attacks(tables,  9, Color::Black), 0x0000000000050000u64);
+    assert_eq!(MoveTables::read_pawn_attacks(tables, 24, Color::Black), 0x0000000200000000u64);
+    assert_eq!(MoveTables::read_pawn_attacks(tables, 31, Color::Black), 0x0000004000000000u64);
+    assert_eq!(MoveTables::read_pawn_attacks(tables, 47, Color::White), 0x0000004000000000u64);
 }
 
 #[test]
@@ -81,9 +81,9 @@ fn asrt_bb(bb: u64, actual: u64, occ: u64, msg: &str) {
         println!("Result: {}", bb);
         println!("Actual: {}\n", actual);
         bitboard::print(occ);
-        println!("");
+        println!();
         bitboard::print_debug(bb, actual);
-        println!("");
+        println!();
     }
     assert_eq!(bb, actual);
 }
@@ -1,7 +1,498 @@
 
+use crate::board::*;
 use crate::generator::*;
+use crate::tests::helpers::*;
+
+struct PerftPosition {
+    fen: &'static str,
+    nodes: &'static [u64]
+}
+
+/// Reference positions and node counts from https://www.chessprogramming.org/Perft_Results.
+const PERFT_POSITIONS: [PerftPosition; 6] = [
+    PerftPosition {
+        fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        nodes: &[ 20, 400, 8902, 197281, 4865609 ]
+    },
+    PerftPosition {
+        fen: "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        nodes: &[ 48, 2039, 97862, 4085603 ]
+    },
+    PerftPosition {
+        fen: "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        nodes: &[ 14, 191, 2812, 43238, 674624 ]
+    },
+    PerftPosition {
+        fen: "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        nodes: &[ 6, 264, 9467, 422333 ]
+    },
+    PerftPosition {
+        fen: "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        nodes: &[ 44, 1486, 62379, 2103487 ]
+    },
+    PerftPosition {
+        fen: "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+        nodes: &[ 46, 2079, 89890, 3894594 ]
+    },
+];
+
+fn check_perft(position: &PerftPosition) {
+    let generator: MoveGenerator = MoveGenerator::new();
+    let mut board: Board = Board::from_fen(position.fen).unwrap();
+
+    for (i, expected) in position.nodes.iter().enumerate() {
+        let depth: u8 = i as u8 + 1;
+        let nodes: u64 = generator.perft(&mut board, depth);
+        if nodes != *expected {
+            for (mv, count) in generator.divide(&mut board, depth) {
+                println!("{}: {}", mv.to_long_algbr(), count);
+            }
+        }
+        assert_eq!(nodes, *expected, "perft({}) of {}", depth, position.fen);
+    }
+
+    // Perft has to leave the board exactly as it found it.
+    assert_eq!(board.to_fen(), position.fen);
+    assert_eq!(board.history.data.len(), 1);
+}
 
 #[test]
-fn test_base_move_gen() {
+fn test_perft_initial() {
+    check_perft(&PERFT_POSITIONS[0]);
+}
+
+#[test]
+fn test_perft_kiwipete() {
+    check_perft(&PERFT_POSITIONS[1]);
+}
+
+#[test]
+fn test_perft_position_3() {
+    check_perft(&PERFT_POSITIONS[2]);
+}
 
+#[test]
+fn test_perft_position_4() {
+    check_perft(&PERFT_POSITIONS[3]);
+}
+
+#[test]
+fn test_perft_position_5() {
+    check_perft(&PERFT_POSITIONS[4]);
+}
+
+#[test]
+fn test_perft_position_6() {
+    check_perft(&PERFT_POSITIONS[5]);
+}
+
+#[test]
+fn test_divide() {
+    let generator: MoveGenerator = MoveGenerator::new();
+    let mut board: Board = Board::from_fen(PERFT_POSITIONS[0].fen).unwrap();
+
+    let counts: Vec<(Move, u64)> = generator.divide(&mut board, 3);
+    assert_eq!(counts.len(), 20);
+    assert_eq!(counts.iter().map(|(_, n)| n).sum::<u64>(), 8902);
+
+    let e2e4: &(Move, u64) = counts.iter().find(|(mv, _)| mv.to_long_algbr() == "e2e4").unwrap();
+    assert_eq!(e2e4.0.get_flags(), DOUBLE_PAWN_PUSH);
+    assert_eq!(e2e4.1, 600);
+}
+
+fn status(fen: &str) -> GameStatus {
+    return MoveGenerator::new().status(&Board::from_fen(fen).unwrap());
+}
+
+#[test]
+fn test_status_mates() {
+    assert_eq!(status("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"), GameStatus::Ongoing);
+
+    // Fool's mate and a back rank mate.
+    let status_mate: GameStatus = status("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+    assert_eq!(status_mate, GameStatus::Checkmate(Color::Black));
+    assert_eq!(status_mate.result(), "0-1");
+    assert_eq!(status("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1"), GameStatus::Checkmate(Color::White));
+
+    // Check that can be escaped is not mate.
+    assert_eq!(status("R5k1/5pp1/8/8/8/8/8/6K1 b - - 0 1"), GameStatus::Ongoing);
+
+    let stalemate: GameStatus = status("7k/5Q2/8/8/8/8/8/6K1 b - - 0 1");
+    assert_eq!(stalemate, GameStatus::Stalemate);
+    assert_eq!(stalemate.result(), "1/2-1/2");
+}
+
+#[test]
+fn test_status_insufficient_material() {
+    assert_eq!(status("8/8/4k3/8/8/3K4/8/8 w - - 0 1"), GameStatus::InsufficientMaterial);
+    assert_eq!(status("8/8/4k3/8/8/3KN3/8/8 w - - 0 1"), GameStatus::InsufficientMaterial);
+    assert_eq!(status("8/8/4k3/8/8/3KB3/8/8 b - - 0 1"), GameStatus::InsufficientMaterial);
+
+    // Bishops on the same color can never mate, on opposite colors they can.
+    assert_eq!(status("8/8/3bk3/8/8/3KB3/8/8 w - - 0 1"), GameStatus::InsufficientMaterial);
+    assert_eq!(status("8/8/2b1k3/8/8/3KB3/8/8 w - - 0 1"), GameStatus::Ongoing);
+    assert_eq!(status("8/8/4k3/8/8/3KNN2/8/8 w - - 0 1"), GameStatus::Ongoing);
+    assert_eq!(status("8/8/4k3/8/8/3K4/7P/8 w - - 0 1"), GameStatus::Ongoing);
+}
+
+#[test]
+fn test_status_fifty_move() {
+    assert_eq!(status("8/8/4k3/8/8/3K4/7R/8 w - - 100 90"), GameStatus::FiftyMove);
+    assert_eq!(status("8/8/4k3/8/8/3K4/7R/8 w - - 99 90"), GameStatus::Ongoing);
+
+    // Mate on the last move still counts.
+    let mut board: Board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 99 90").unwrap();
+    play(&mut board, &[ "a1a8" ]);
+    assert!(board.is_fifty_move_draw());
+    assert_eq!(MoveGenerator::new().status(&board), GameStatus::Checkmate(Color::White));
+}
+
+#[test]
+fn test_status_repetition() {
+    let generator: MoveGenerator = MoveGenerator::new();
+    let mut board: Board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+
+    play(&mut board, &[ "g1f3", "g8f6", "f3g1", "f6g8" ]);
+    assert_eq!(generator.status(&board), GameStatus::Ongoing);
+    play(&mut board, &[ "g1f3", "g8f6", "f3g1", "f6g8" ]);
+    assert_eq!(generator.status(&board), GameStatus::Repetition);
+    assert_eq!(GameStatus::Repetition.winner(), None);
+}
+
+#[test]
+fn test_status_hooks() {
+    assert_eq!(GameStatus::Timeout(Color::White).winner(), Some(Color::Black));
+    assert_eq!(GameStatus::Resign(Color::Black).result(), "1-0");
+    assert!(GameStatus::Resign(Color::Black).is_over());
+    assert!(!GameStatus::Ongoing.is_over());
+    assert_eq!(GameStatus::Ongoing.result(), "*");
+}
+
+/// Checks that is_legal accepts exactly the generated moves out of every move a piece of the side
+/// to move could be given, including the ones with malformed flags.
+fn check_is_legal(generator: &MoveGenerator, board: &Board) {
+    let state: BoardTables = generator.gen_board_tables(board);
+    let mut legal: MoveList = MoveList::new();
+    generator.gen_moves(&mut legal, board, &state);
+
+    let mut accepted: usize = 0;
+    for from in 0..64u16 {
+        if board.bitboard.color[board.turn as usize] & (1u64 << from) == 0 {
+            continue;
+        }
+        for to in 0..64u16 {
+            for flags in 0..16u16 {
+                let mv: Move = Move::new_raw(from, to, flags << 12);
+                if generator.is_legal(board, &state, &mv) {
+                    assert!((0..legal.size()).any(|i| *legal.at(i) == mv),
+                        "{} accepted in {}", mv.to_long_algbr(), board.to_fen());
+                    accepted += 1;
+                }
+            }
+        }
+    }
+    assert_eq!(accepted, legal.size(), "moves rejected in {}", board.to_fen());
+}
+
+#[test]
+fn test_is_legal() {
+    let generator: MoveGenerator = MoveGenerator::new();
+    for position in &PERFT_POSITIONS {
+        let mut board: Board = Board::from_fen(position.fen).unwrap();
+        check_is_legal(&generator, &board);
+
+        let moves: MoveList = legal_moves(&board);
+        for i in 0..moves.size() {
+            board.make(moves.at(i));
+            check_is_legal(&generator, &board);
+            board.unmake();
+        }
+    }
+}
+
+fn sorted_algbr(moves: &MoveList) -> Vec<String> {
+    let mut algbr: Vec<String> = (0..moves.size()).map(|i| moves.at(i).to_long_algbr()).collect();
+    algbr.sort();
+    return algbr;
+}
+
+/// Checks that the generation modes split up the legal moves in every position of the tree.
+fn check_gen_modes(generator: &MoveGenerator, board: &mut Board, depth: u8) {
+    let state: BoardTables = generator.gen_board_tables(board);
+    let mut all: MoveList = MoveList::new();
+    let mut captures: MoveList = MoveList::new();
+    let mut quiets: MoveList = MoveList::new();
+    generator.gen_moves(&mut all, board, &state);
+    generator.gen_moves_mode(&mut captures, board, &state, GenMode::Captures);
+    generator.gen_moves_mode(&mut quiets, board, &state, GenMode::Quiets);
+
+    for i in 0..captures.size() {
+        let mv: &Move = captures.at(i);
+        assert!(mv.is_capture() || mv.is_promotion(), "{} in captures", mv.to_long_algbr());
+    }
+    for i in 0..quiets.size() {
+        let mv: &Move = quiets.at(i);
+        assert!(!mv.is_capture() && !mv.is_promotion(), "{} in quiets", mv.to_long_algbr());
+    }
+    let mut split: Vec<String> = sorted_algbr(&captures);
+    split.extend(sorted_algbr(&quiets));
+    split.sort();
+    assert_eq!(split, sorted_algbr(&all), "in {}", board.to_fen());
+
+    if state.checks != 0 {
+        let mut evasions: MoveList = MoveList::new();
+        generator.gen_moves_mode(&mut evasions, board, &state, GenMode::Evasions);
+        assert_eq!(sorted_algbr(&evasions), sorted_algbr(&all), "in {}", board.to_fen());
+    }
+
+    if depth > 1 {
+        for i in 0..all.size() {
+            board.make(all.at(i));
+            check_gen_modes(generator, board, depth - 1);
+            board.unmake();
+        }
+    }
+}
+
+#[test]
+fn test_gen_modes() {
+    let generator: MoveGenerator = MoveGenerator::new();
+    for position in &PERFT_POSITIONS {
+        let mut board: Board = Board::from_fen(position.fen).unwrap();
+        check_gen_modes(&generator, &mut board, 3);
+    }
+}
+
+#[test]
+fn test_evasions_double_check() {
+    let generator: MoveGenerator = MoveGenerator::new();
+    let board: Board = Board::from_fen("4r1k1/8/8/8/1b6/8/8/4K2R w K - 0 1").unwrap();
+    let state: BoardTables = generator.gen_board_tables(&board);
+    let mut evasions: MoveList = MoveList::new();
+    generator.gen_moves_mode(&mut evasions, &board, &state, GenMode::Evasions);
+    assert_eq!(sorted_algbr(&evasions), [ "e1d1", "e1f1", "e1f2" ]);
+}
+
+/// Collects every move handed out by the staged moves.
+fn staged(board: &Board, hash_move: Option<Move>, killers: [Option<Move>; 2]) -> Vec<Move> {
+    let mut staged: StagedMoves = StagedMoves::new(board, hash_move, killers);
+    let mut moves: Vec<Move> = vec![];
+    while let Some(mv) = staged.next_move(board, |_| 0) {
+        moves.push(mv);
+    }
+    return moves;
+}
+
+#[test]
+fn test_staged_moves() {
+    let generator: MoveGenerator = MoveGenerator::new();
+    let board: Board = Board::from_fen(PERFT_POSITIONS[1].fen).unwrap();
+    let state: BoardTables = generator.gen_board_tables(&board);
+    let mut all: MoveList = MoveList::new();
+    generator.gen_moves(&mut all, &board, &state);
+
+    // Without any hints every move comes out once, the captures first.
+    let moves: Vec<Move> = staged(&board, None, [ None, None ]);
+    let mut algbr: Vec<String> = moves.iter().map(|mv| mv.to_long_algbr()).collect();
+    algbr.sort();
+    assert_eq!(algbr, sorted_algbr(&all));
+    let is_quiet = |mv: &Move| !mv.is_capture() && !mv.is_promotion();
+    let first_quiet: usize = moves.iter().position(is_quiet).unwrap();
+    assert!(moves[first_quiet..].iter().all(is_quiet));
+
+    // The hash move comes first and the killers right after the captures. Illegal and repeated
+    // hints are dropped.
+    let hash_move: Move = mv("e1", "g1", KING_SIDE_CASTLE);
+    let killer: Move = mv("a2", "a3", QUIET);
+    let hints: [Option<Move>; 2] = [ Some(killer.clone()), Some(killer.clone()) ];
+    let moves: Vec<Move> = staged(&board, Some(hash_move.clone()), hints);
+    assert_eq!(moves.len(), all.size());
+    assert_eq!(moves[0], hash_move);
+    assert_eq!(moves[first_quiet + 1], killer);
+    assert_eq!(moves.iter().filter(|mv| **mv == killer).count(), 1);
+
+    let hints: [Option<Move>; 2] = [ Some(mv("e5", "f7", QUIET)), None ];
+    let moves: Vec<Move> = staged(&board, Some(mv("a2", "a5", QUIET)), hints);
+    assert_eq!(moves.len(), all.size());
+}
+
+#[test]
+fn test_staged_moves_in_check() {
+    // Hints that don't get out of check are dropped.
+    let board: Board = Board::from_fen("4r1k1/8/8/8/8/3P4/R7/4K3 w - - 0 1").unwrap();
+    let hints: [Option<Move>; 2] = [ Some(mv("a2", "a3", QUIET)), None ];
+    let moves: Vec<Move> = staged(&board, Some(mv("d3", "d4", QUIET)), hints);
+    let mut algbr: Vec<String> = moves.iter().map(|mv| mv.to_long_algbr()).collect();
+    algbr.sort();
+    assert_eq!(algbr, [ "a2e2", "e1d1", "e1d2", "e1f1", "e1f2" ]);
+
+    // A hash move that does is returned first.
+    let moves: Vec<Move> = staged(&board, Some(mv("a2", "e2", QUIET)), [ None, None ]);
+    assert_eq!(moves.len(), 5);
+    assert_eq!(moves[0], mv("a2", "e2", QUIET));
+}
+
+fn squares(names: &[&str]) -> u64 {
+    return names.iter().fold(0, |bb, name| bb | name.parse::<Square>().unwrap().bit());
+}
+
+#[test]
+fn test_attackers_to() {
+    let generator: MoveGenerator = MoveGenerator::new();
+    let board: Board = Board::from_fen(START_FEN).unwrap();
+    let occupancy: u64 = board.bitboard.occupancy;
+    let f3: Square = "f3".parse().unwrap();
+    let white: u64 = generator.attackers_to(&board, f3, occupancy, Color::White);
+    assert_eq!(white, squares(&[ "e2", "g2", "g1" ]));
+    assert_eq!(generator.attackers_to(&board, f3, occupancy, Color::Black), 0);
+    assert!(generator.is_attacked(&board, "d1".parse().unwrap(), Color::White));
+    assert!(!generator.is_attacked(&board, "e4".parse().unwrap(), Color::White));
+    assert!(generator.is_attacked(&board, "a6".parse().unwrap(), Color::Black));
+
+    // Taking a piece out of the occupancy uncovers the pieces behind it.
+    let board: Board = Board::from_fen("3rk3/3r4/8/8/3p4/2P5/8/3QK3 w - - 0 1").unwrap();
+    let d4: Square = "d4".parse().unwrap();
+    let occupancy: u64 = board.bitboard.occupancy;
+    let white: u64 = generator.attackers_to(&board, d4, occupancy, Color::White);
+    assert_eq!(white, squares(&[ "c3", "d1" ]));
+    assert_eq!(generator.attackers_to(&board, d4, occupancy, Color::Black), squares(&[ "d7" ]));
+    let without_d7: u64 = occupancy & !squares(&[ "d7" ]);
+    assert_eq!(generator.attackers_to(&board, d4, without_d7, Color::Black), squares(&[ "d8" ]));
+    assert_eq!(generator.all_attackers_to(&board, d4, occupancy), squares(&[ "c3", "d1", "d7" ]));
+}
+
+/// Checks gives_check and the checking move generation against making each move in the tree.
+fn check_gives_check(generator: &MoveGenerator, board: &mut Board, depth: u8) {
+    let state: BoardTables = generator.gen_board_tables(board);
+    let mut all: MoveList = MoveList::new();
+    let mut checks: MoveList = MoveList::new();
+    generator.gen_moves(&mut all, board, &state);
+    generator.gen_moves_mode(&mut checks, board, &state, GenMode::Checks);
+
+    let mut expected: MoveList = MoveList::new();
+    for i in 0..all.size() {
+        let mv: &Move = all.at(i);
+        let gives_check: bool = generator.gives_check(board, mv);
+        board.make(mv);
+        let in_check: bool = generator.gen_board_tables(board).checks != 0;
+        assert_eq!(gives_check, in_check, "{}", board.to_fen());
+        if gives_check {
+            expected.push(mv.clone());
+        }
+        if depth > 1 {
+            check_gives_check(generator, board, depth - 1);
+        }
+        board.unmake();
+    }
+    assert_eq!(sorted_algbr(&checks), sorted_algbr(&expected));
+}
+
+#[test]
+fn test_gives_check() {
+    let generator: MoveGenerator = MoveGenerator::new();
+    for position in &PERFT_POSITIONS {
+        let mut board: Board = Board::from_fen(position.fen).unwrap();
+        check_gives_check(&generator, &mut board, 3);
+    }
+
+    // Castling checks with the rook, enpassant can uncover a check and promotions check with the
+    // new piece.
+    let special: [(&str, Move); 3] = [
+        ("5k2/8/8/8/8/8/8/4K2R w K - 0 1", mv("e1", "g1", KING_SIDE_CASTLE)),
+        ("8/8/8/1k1pP2R/8/8/8/4K3 w - d6 0 1", mv("e5", "d6", ENPASSANT)),
+        ("3k4/P7/8/8/8/8/8/4K3 w - - 0 1", mv("a7", "a8", QUEEN_PROMO)),
+    ];
+    for (fen, mv) in &special {
+        let board: Board = Board::from_fen(fen).unwrap();
+        assert!(generator.gives_check(&board, mv), "{} in {}", mv.to_long_algbr(), fen);
+    }
+    let board: Board = Board::from_fen("3k4/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert!(!generator.gives_check(&board, &mv("a7", "a8", KNIGHT_PROMO)));
+}
+
+/// Static exchange positions with the expected result using SEE_VALUES. Most of them are from the
+/// SEE test suite that circulates between engine authors.
+const SEE_POSITIONS: [(&str, &str, i32); 35] = [
+    ("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1", "Rxe5", 100),
+    ("1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1", "Nxe5", -200),
+    ("6k1/1pp4p/p1pb4/6q1/3P1pRr/2P4P/PP1Br1P1/5RKN w - - 0 1", "Rfxf4", -100),
+    ("5rk1/1pp2q1p/p1pb4/8/3P1NP1/2P5/1P1BQ1P1/5RK1 b - - 0 1", "Bxf4", 0),
+    ("4R3/2r3p1/5bk1/1p1r3p/p2PR1P1/P1BK1P2/1P6/8 b - - 0 1", "hxg4", 0),
+    ("4R3/2r3p1/5bk1/1p1r1p1p/p2PR1P1/P1BK1P2/1P6/8 b - - 0 1", "hxg4", 0),
+    ("4r1k1/5pp1/nbp4p/1p2p2q/1P2P1b1/1BP2N1P/1B2QPPK/3R4 b - - 0 1", "Bxf3", 0),
+    ("2r1r1k1/pp1bppbp/3p1np1/q3P3/2P2P2/1P2B3/P1N1B1PP/2RQ1RK1 b - - 0 1", "dxe5", 100),
+    ("7r/5qpk/p1Qp1b1p/3r3n/BB3p2/5p2/P1P2P2/4RK1R w - - 0 1", "Re8", 0),
+    ("6rr/6pk/p1Qp1b1p/2n5/1B3p2/5p2/P1P2P2/4RK1R w - - 0 1", "Re8", -500),
+    ("7r/5qpk/2Qp1b1p/1N1r3n/BB3p2/5p2/P1P2P2/4RK1R w - - 0 1", "Re8", -500),
+    ("6RR/4bP2/8/8/5r2/3K4/5p2/4k3 w - - 0 1", "f8=Q", 200),
+    ("6RR/4bP2/8/8/5r2/3K4/5p2/4k3 w - - 0 1", "f8=N", 200),
+    ("7R/5P2/8/8/6r1/3K4/5p2/4k3 w - - 0 1", "f8=Q", 800),
+    ("7R/5P2/8/8/6r1/3K4/5p2/4k3 w - - 0 1", "f8=B", 200),
+    ("7R/4bP2/8/8/1q6/3K4/5p2/4k3 w - - 0 1", "f8=R", -100),
+    ("8/4kp2/2npp3/1Nn5/1p2PQP1/7q/1PP1B3/4KR1r b - - 0 1", "Rxf1+", 0),
+    ("8/4kp2/2npp3/1Nn5/1p2P1P1/7q/1PP1B3/4KR1r b - - 0 1", "Rxf1+", 0),
+    ("2r2r1k/6bp/p7/2q2p1Q/3PpP2/1B6/P5PP/2RR3K b - - 0 1", "Qxc1", 100),
+    ("r2qk1nr/pp2ppbp/2b3p1/2p1p3/8/2N2N2/PPPP1PPP/R1BQR1K1 w kq - 0 1", "Nxe5", 100),
+    ("6r1/4kq2/b2p1p2/p1pPb3/p1P2B1Q/2P4P/2B1R1P1/6K1 w - - 0 1", "Bxe5", 0),
+    ("3q2nk/pb1r1p2/np6/3P2Pp/2p1P3/2R4B/PQ3P1P/3R2K1 w - h6 0 1", "gxh6", 0),
+    ("2r4r/1P4pk/p2p1b1p/7n/BB3p2/2R2p2/P1P2P2/4RK2 w - - 0 1", "Rxc8", 500),
+    ("2r5/1P4pk/p2p1b1p/5b1n/BB3p2/2R2p2/P1P2P2/4RK2 w - - 0 1", "Rxc8", 500),
+    ("2r4k/2r4p/p7/2b2p1b/4pP2/1BR5/P1R3PP/2Q4K w - - 0 1", "Rxc5", 300),
+    ("8/pp6/2pkp3/4bp2/2R3b1/2P5/PP4B1/1K6 w - - 0 1", "Bxc6", -200),
+    ("3r3k/3r4/2n1n3/8/3p4/2PR4/1B1Q4/3R3K w - - 0 1", "Rxd4", -100),
+    ("1k1r4/1ppn3p/p4b2/4n3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1", "Nxe5", 100),
+    ("rnb2b1r/ppp2kpp/5n2/4P3/q2P3B/5R2/PPP2PPP/RN1QKB2 w Q - 0 1", "Bxf6", 100),
+    ("r2q1rk1/2p1bppp/p2p1n2/1p2P3/4P1b1/1nP1BN2/PP3PPP/RN1QR1K1 b - - 0 1", "Bxf3", 0),
+    ("r1bqkb1r/2pp1ppp/p1n5/1p2p3/3Pn3/1B3N2/PPP2PPP/RNBQ1RK1 b kq - 0 1", "Nxd4", 0),
+    ("r1bq1r2/pp1ppkbp/4N1p1/n3P1B1/8/2N5/PPP2PPP/R2QK2R w KQ - 0 1", "Nxg7", 0),
+    ("r1bq1r2/pp1ppkbp/4N1pB/n3P3/8/2N5/PPP2PPP/R2QK2R w KQ - 0 1", "Nxg7", 300),
+    ("rnq1k2r/1b3ppp/p2bpn2/1p1p4/3N4/1BN1P3/PPP2PPP/R1BQR1K1 b kq - 0 1", "Bxh2", -200),
+    ("rn2k2r/1bq2ppp/p2bpn2/1p1p4/3N4/1BN1P3/PPP2PPP/R1BQR1K1 b kq - 0 1", "Bxh2", 100),
+];
+
+#[test]
+fn test_see() {
+    let generator: MoveGenerator = MoveGenerator::new();
+    for (fen, san, expected) in &SEE_POSITIONS {
+        let board: Board = Board::from_fen(fen).unwrap();
+        let mv: Move = Move::from_short_algbr(san, &board, &legal_moves(&board)).unwrap();
+
+        assert_eq!(generator.see(&board, &mv), *expected, "{} in {}", san, fen);
+        assert!(generator.see_ge(&board, &mv, *expected), "{} in {}", san, fen);
+        assert!(!generator.see_ge(&board, &mv, *expected + 1), "{} in {}", san, fen);
+    }
+}
+
+/// Checks that see_ge agrees with see for every move in the tree around each threshold.
+fn check_see_ge(generator: &MoveGenerator, board: &mut Board, depth: u8) {
+    let state: BoardTables = generator.gen_board_tables(board);
+    let mut moves: MoveList = MoveList::new();
+    generator.gen_moves(&mut moves, board, &state);
+
+    for i in 0..moves.size() {
+        let mv: &Move = moves.at(i);
+        let see: i32 = generator.see(board, mv);
+        for threshold in [ see - 1, see, see + 1, -100, 0, 100 ] {
+            assert_eq!(generator.see_ge(board, mv, threshold), see >= threshold,
+                "{} >= {} in {}", mv.to_long_algbr(), threshold, board.to_fen());
+        }
+        if depth > 1 {
+            board.make(mv);
+            check_see_ge(generator, board, depth - 1);
+            board.unmake();
+        }
+    }
+}
+
+#[test]
+fn test_see_ge() {
+    let generator: MoveGenerator = MoveGenerator::new();
+    for position in &PERFT_POSITIONS {
+        let mut board: Board = Board::from_fen(position.fen).unwrap();
+        check_see_ge(&generator, &mut board, 2);
+    }
+    for (fen, _, _) in &SEE_POSITIONS {
+        let mut board: Board = Board::from_fen(fen).unwrap();
+        check_see_ge(&generator, &mut board, 1);
+    }
 }
@@ -1,7 +1,23 @@
 
+#![allow(
+    // Functions end with an explicit return.
+    clippy::needless_return,
+    // Square and piece arithmetic casts to the type it works in, even when it already has it.
+    clippy::unnecessary_cast,
+    // Loops over squares, pieces and colors use the index for several tables at once.
+    clippy::needless_range_loop,
+    // Constructors like MoveGenerator::new build tables, which a Default impl would hide.
+    clippy::new_without_default
+)]
+
+pub mod types;
 pub mod board;
 pub mod moves;
 pub mod generator;
+pub mod pgn;
+pub mod eval;
+pub mod ordering;
+pub mod search;
 
 #[cfg(test)]
 mod tests;
@@ -25,7 +25,7 @@ const FLAG_MASK: u16    = 0xF << 12;
 pub const MAX_NUM_MOVES: usize = 218;
 pub const INVALID_MOVE: u16 = 0b0110111111111111;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Move {
     data: u16
 }
@@ -41,74 +41,68 @@ impl Move {
         }
 
         let to: u8 = self.get_to();
-        let start_piece: u8 = board_before_move.type_at_sq(self.get_from());
+        let from: u8 = self.get_from();
+        let start_piece: u8 = board_before_move.type_at_sq(from);
 
-        let from_file: char = (self.get_from() % 8  + 'a' as u8) as char;
-        let from_rank: char = ('8' as u8 - self.get_from() / 8) as char;
-        let to_file: char = (self.get_to() % 8 + 'a' as u8) as char;
-        let to_rank: char = ('8' as u8 - self.get_to() / 8) as char;
+        let from_file: char = (from % 8  + b'a') as char;
+        let from_rank: char = (b'8' - from / 8) as char;
+        let to_file: char = (to % 8 + b'a') as char;
+        let to_rank: char = (b'8' - to / 8) as char;
 
+        let mut conflict: bool = false;
         let mut from_file_conflict: bool = false;
         let mut from_rank_conflict: bool = false;
 
-        // Check for conflicting moves for this position.
-        for alternative in &moves.moves[..] {
-            let alt_to: u8 = alternative.get_to();
-            let alt_start_piece: u8 = board_before_move.type_at_sq(alternative.get_from());
-
-            if to == alt_to && start_piece == alt_start_piece {
-                let alt_from_file: char = (alternative.get_from() % 8  + 'a' as u8) as char;
-                let alt_from_rank: char = ('8' as u8 - alternative.get_from() / 8) as char;
-
-                from_file_conflict = from_file == alt_from_file;
-                from_rank_conflict = from_rank == alt_from_rank;
+        // Check for other pieces of the same type that can move to the same square.
+        for i in 0..moves.size() {
+            let alternative: &Move = moves.at(i);
+            let alt_from: u8 = alternative.get_from();
+            if alternative.get_to() != to || alt_from == from
+                || board_before_move.type_at_sq(alt_from) != start_piece {
+                continue;
             }
+
+            conflict = true;
+            from_file_conflict |= alt_from % 8 == from % 8;
+            from_rank_conflict |= alt_from / 8 == from / 8;
         }
 
         let mut algbr = String::new();
-        algbr.push(to_file);
-        algbr.push(to_rank);
-
-        match self.get_flags() {
-            CAPTURE => algbr.insert(0, 'x'),
-            KNIGHT_PROMO => algbr = algbr + "=N",
-            BISHOP_PROMO => algbr = algbr + "=B",
-            ROOK_PROMO => algbr = algbr + "=R",
-            QUEEN_PROMO => algbr = algbr + "=Q",
-            KNIGHT_PROMO_CAPTURE => {
-                algbr = algbr + "=N";
-                algbr.insert(0, 'x');
-            },
-            BISHOP_PROMO_CAPTURE => {
-                algbr = algbr + "=B";
-                algbr.insert(0, 'x');
-            },
-            ROOK_PROMO_CAPTURE => {
-                algbr = algbr + "=R";
-                algbr.insert(0, 'x');
-            },
-            QUEEN_PROMO_CAPTURE => {
-                algbr = algbr + "=Q";
-                algbr.insert(0, 'x');
-            },
+        match start_piece as usize {
+            board::KNIGHT => algbr.push('N'),
+            board::BISHOP => algbr.push('B'),
+            board::ROOK   => algbr.push('R'),
+            board::QUEEN  => algbr.push('Q'),
+            board::KING   => algbr.push('K'),
             _ => ()
         }
 
-        if from_file_conflict {
-            algbr.insert(0, from_rank);
+        // Pawn captures are always identified by their file. Other pieces prefer the file, then
+        // the rank, then both when there are multiple candidates for the move.
+        if start_piece as usize == board::PAWN {
+            if self.is_capture() {
+                algbr.push(from_file);
+            }
+        } else if conflict {
+            if !from_file_conflict {
+                algbr.push(from_file);
+            } else if !from_rank_conflict {
+                algbr.push(from_rank);
+            } else {
+                algbr.push(from_file);
+                algbr.push(from_rank);
+            }
         }
 
-        if from_rank_conflict {
-            algbr.insert(0, from_file);
+        if self.is_capture() {
+            algbr.push('x');
         }
+        algbr.push(to_file);
+        algbr.push(to_rank);
 
-        match start_piece as usize {
-            board::KNIGHT => algbr.insert(0, 'N'),
-            board::BISHOP => algbr.insert(0, 'B'),
-            board::ROOK   => algbr.insert(0, 'R'),
-            board::QUEEN  => algbr.insert(0, 'Q'),
-            board::KING   => algbr.insert(0, 'K'),
-            _ => ()
+        if self.is_promotion() {
+            algbr.push('=');
+            algbr.push(self.promo_piece().unwrap().to_char().to_ascii_uppercase());
         }
 
         return algbr;
@@ -117,13 +111,13 @@ impl Move {
     /// Returns the UCI algebraic notatition string for the move. 
     pub fn to_long_algbr(&self) -> String {
         let mut algbr: String = String::new();
-        algbr.push((self.get_from() % 8 + 'a' as u8) as char);
-        algbr.push(('8' as u8 - self.get_from() / 8) as char);
-        algbr.push((self.get_to() % 8 + 'a' as u8) as char);
-        algbr.push(('8' as u8 - self.get_to() / 8) as char);
+        algbr.push((self.get_from() % 8 + b'a') as char);
+        algbr.push((b'8' - self.get_from() / 8) as char);
+        algbr.push((self.get_to() % 8 + b'a') as char);
+        algbr.push((b'8' - self.get_to() / 8) as char);
 
         match self.get_flags() {
-            KNIGHT_PROMO | KNIGHT_PROMO_CAPTURE => algbr.push('k'),
+            KNIGHT_PROMO | KNIGHT_PROMO_CAPTURE => algbr.push('n'),
             BISHOP_PROMO | BISHOP_PROMO_CAPTURE => algbr.push('b'),
             ROOK_PROMO   | ROOK_PROMO_CAPTURE   => algbr.push('r'),
             QUEEN_PROMO  | QUEEN_PROMO_CAPTURE  => algbr.push('q'),
@@ -133,11 +127,21 @@ impl Move {
         return algbr;
     }
 
-    pub fn get_to(&self) -> u8 {
+    /// Gets the square that the moving piece lands on.
+    pub fn to_sq(&self) -> board::Square {
+        return board::Square::from_raw(self.get_to());
+    }
+
+    /// Gets the square that the moving piece starts on.
+    pub fn from_sq(&self) -> board::Square {
+        return board::Square::from_raw(self.get_from());
+    }
+
+    pub(crate) fn get_to(&self) -> u8 {
         return (self.data & TO_MASK) as u8;
     }
 
-    pub fn get_from(&self) -> u8 {
+    pub(crate) fn get_from(&self) -> u8 {
         return ((self.data & FROM_MASK) >> 6) as u8;
     }
 
@@ -145,8 +149,18 @@ impl Move {
         return self.data & FLAG_MASK;
     }
 
-    /// Builds the move the raw underlying data.
-    pub fn new(from: u16, to: u16, flags: u16) -> Move {
+    /// Gets the raw 16 bits of the move, the inverse of from_data.
+    pub fn get_data(&self) -> u16 {
+        return self.data;
+    }
+
+    /// Builds a move from its squares and one of the move flags.
+    pub fn new(from: board::Square, to: board::Square, flags: u16) -> Move {
+        return Move::new_raw(from.index() as u16, to.index() as u16, flags);
+    }
+
+    /// Builds the move from raw square indices.
+    pub(crate) fn new_raw(from: u16, to: u16, flags: u16) -> Move {
         return Move {
             data: flags | (from << 6) | to
         }
@@ -158,33 +172,200 @@ impl Move {
         }
     }
 
+    /// Returns true if the flags of the move name one of the kinds of moves. The two unused flag
+    /// values (which INVALID_MOVE uses) don't.
+    pub fn has_valid_flags(&self) -> bool {
+        let flags: u16 = self.get_flags();
+        return flags != 6 << 12 && flags != 7 << 12;
+    }
+
+    /// Returns true if the move removes an enemy piece from the board.
+    pub fn is_capture(&self) -> bool {
+        return self.data & CAPTURE != 0;
+    }
+
+    /// Returns true if the move promotes a pawn.
+    pub fn is_promotion(&self) -> bool {
+        return self.data & KNIGHT_PROMO != 0;
+    }
+
+    /// Gets the piece type that a pawn is promoted to, if the move is a promotion.
+    pub fn promo_piece(&self) -> Option<board::PieceType> {
+        if !self.is_promotion() {
+            return None;
+        }
+        return board::PieceType::from_index(((self.get_flags() >> 12) & 0b11) as u8 + board::KNIGHT as u8);
+    }
+
     /// Builds the move from a FIDE algebraic string representation of the move.
-    pub fn from_short_algbr(algbr: &str, moves: &MoveList) {
-        let piece: u8 = match algbr.as_bytes()[0 as usize] as char {
-            'N' => board::KNIGHT as u8,
-            'B' => board::BISHOP as u8,
-            'R' => board::ROOK as u8,
-            'Q' => board::QUEEN as u8,
-            'K' => board::KING as u8,
-            _ => board::PAWN as u8 
+    ///
+    /// The string is resolved against the list of legal moves for the board, so it accepts
+    /// anything that uniquely identifies one of them: disambiguation by file, rank or square,
+    /// promotions with or without '=', castles written with 'O' or '0', and trailing check,
+    /// mate or annotation symbols.
+    pub fn from_short_algbr<'a>(
+        algbr: &'a str,
+        board: &board::Board,
+        moves: &MoveList
+    ) -> Result<Move, board::PgnError<'a>> {
+        let trimmed: &str = algbr.trim_end_matches(['+', '#', '!', '?']);
+
+        // Castles are matched by their flag alone.
+        let castle: Option<u16> = match trimmed {
+            "O-O" | "0-0" => Some(KING_SIDE_CASTLE),
+            "O-O-O" | "0-0-0" => Some(QUEEN_SIDE_CASTLE),
+            _ => None
         };
+        if let Some(flag) = castle {
+            for i in 0..moves.size() {
+                if moves.at(i).get_flags() == flag {
+                    return Ok(moves.at(i).clone());
+                }
+            }
+            return Err(board::PgnError::PgnInvalidMoveError(algbr));
+        }
+
+        let mut chars: Vec<char> = trimmed.chars().collect();
+        let malformed = board::PgnError::PgnMalformedError(algbr);
+
+        let piece: u8 = match chars.first() {
+            Some('N') => board::KNIGHT as u8,
+            Some('B') => board::BISHOP as u8,
+            Some('R') => board::ROOK as u8,
+            Some('Q') => board::QUEEN as u8,
+            Some('K') => board::KING as u8,
+            Some('a'..='h') => board::PAWN as u8,
+            _ => return Err(malformed)
+        };
+        if piece as usize != board::PAWN {
+            chars.remove(0);
+        }
 
-        let mut flag: u16 = match algbr.chars().last() {
-            Some('N') => KNIGHT_PROMO,
-            Some('B') => BISHOP_PROMO,
-            Some('R') => ROOK_PROMO,
-            Some('Q') => QUEEN_PROMO,
-            _ => 0
+        // Strip the promotion piece off of the end.
+        let promo: Option<board::PieceType> = match chars.last() {
+            Some('N') => Some(board::PieceType::Knight),
+            Some('B') => Some(board::PieceType::Bishop),
+            Some('R') => Some(board::PieceType::Rook),
+            Some('Q') => Some(board::PieceType::Queen),
+            _ => None
         };
+        if promo.is_some() {
+            chars.pop();
+            if chars.last() == Some(&'=') {
+                chars.pop();
+            }
+            if piece as usize != board::PAWN {
+                return Err(malformed);
+            }
+        }
+
+        // The target square is always last, anything in front of it narrows down the source.
+        if chars.len() < 2 {
+            return Err(malformed);
+        }
+        let to_rank: char = chars.pop().unwrap();
+        let to_file: char = chars.pop().unwrap();
+        if !('a'..='h').contains(&to_file) || !('1'..='8').contains(&to_rank) {
+            return Err(malformed);
+        }
+        let to: u8 = (b'8' - to_rank as u8) * 8 + (to_file as u8 - b'a');
+
+        if chars.last() == Some(&'x') {
+            chars.pop();
+        }
+        let mut from_file: Option<u8> = None;
+        let mut from_rank: Option<u8> = None;
+        for c in chars {
+            match c {
+                'a'..='h' if from_file.is_none() && from_rank.is_none() => {
+                    from_file = Some(c as u8 - b'a');
+                },
+                '1'..='8' if from_rank.is_none() => {
+                    from_rank = Some(b'8' - c as u8);
+                },
+                _ => return Err(malformed)
+            }
+        }
 
-        flag += if algbr.contains('x') { 4 } else { 0 };
+        let mut found: Option<&Move> = None;
+        for i in 0..moves.size() {
+            let mv: &Move = moves.at(i);
+            let from: u8 = mv.get_from();
+            let matches: bool = mv.get_to() == to
+                && board.type_at_sq(from) == piece
+                && from_file.is_none_or(|file| from % 8 == file)
+                && from_rank.is_none_or(|rank| from / 8 == rank)
+                && match promo {
+                    Some(ptype) => mv.promo_piece() == Some(ptype),
+                    None => !mv.is_promotion()
+                };
+
+            if matches {
+                if found.is_some() {
+                    // More than one legal move fits the description.
+                    return Err(board::PgnError::PgnInvalidMoveError(algbr));
+                }
+                found = Some(mv);
+            }
+        }
 
-        todo!();
+        return found.cloned().ok_or(board::PgnError::PgnInvalidMoveError(algbr));
     }
 
     /// Builds the move from a UCI algebraic notation string representation of the move.
-    pub fn from_uci_algbr(algbr: &str, moves: &MoveList) {
-        todo!();
+    ///
+    /// UCI only gives the source, target and promotion piece, so the move is looked up in the
+    /// list of legal moves to recover the rest of its flags.
+    pub fn from_uci_algbr<'a>(
+        algbr: &'a str,
+        moves: &MoveList
+    ) -> Result<Move, board::UciError<'a>> {
+        let bytes: &[u8] = algbr.as_bytes();
+        if bytes.len() != 4 && bytes.len() != 5 {
+            return Err(board::UciError::UciMalformedError(algbr));
+        }
+
+        let mut squares: [u8; 2] = [0; 2];
+        for (i, sq) in squares.iter_mut().enumerate() {
+            let file: u8 = bytes[i * 2];
+            let rank: u8 = bytes[i * 2 + 1];
+            if !(b'a'..=b'h').contains(&file) || !(b'1'..=b'8').contains(&rank) {
+                return Err(board::UciError::UciMalformedError(algbr));
+            }
+            *sq = (b'8' - rank) * 8 + (file - b'a');
+        }
+        let [from, to] = squares;
+        if from == to {
+            return Err(board::UciError::UciInvalidMoveError(algbr));
+        }
+
+        let promo: Option<u16> = match bytes.get(4) {
+            None => None,
+            Some(b'n') => Some(KNIGHT_PROMO),
+            Some(b'b') => Some(BISHOP_PROMO),
+            Some(b'r') => Some(ROOK_PROMO),
+            Some(b'q') => Some(QUEEN_PROMO),
+            Some(_) => return Err(board::UciError::UciMalformedError(algbr))
+        };
+
+        for i in 0..moves.size() {
+            let mv: &Move = moves.at(i);
+            if mv.get_from() != from || mv.get_to() != to {
+                continue;
+            }
+
+            // Promotions with and without captures only differ by the capture bit.
+            let matches: bool = match promo {
+                Some(flag) => mv.is_promotion() && mv.get_flags() & !CAPTURE == flag,
+                None => !mv.is_promotion()
+            };
+            if matches {
+                return Ok(mv.clone());
+            }
+        }
+
+        let flag: u16 = promo.unwrap_or(QUIET);
+        return Err(board::UciError::UciIllegalMoveError(Move::new_raw(from as u16, to as u16, flag)));
     }
 }
 
@@ -254,4 +435,9 @@ impl MoveList {
     pub fn at(&self, idx: usize) -> &Move {
         &self.moves[idx as usize]
     }
+
+    /// Swaps two elements of the MoveList.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.moves.swap(a, b);
+    }
 }
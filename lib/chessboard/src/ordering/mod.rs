@@ -0,0 +1,252 @@
+
+use crate::board::{self, *};
+use crate::moves::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Base score of captures and promotions among the evasions, the MVV-LVA score is added on top.
+pub const CAPTURE_SCORE: i32 = 1 << 28;
+/// Score of the move that refuted the previous move last time.
+pub const COUNTER_MOVE_SCORE: i32 = 1 << 26;
+/// History scores stay between plus and minus this.
+pub const MAX_HISTORY: i32 = 1 << 14;
+
+/// Value of each piece type for MVV-LVA, indexed by piece type.
+const MVV_LVA_VALUES: [i32; 6] = [ 1, 3, 3, 5, 9, 0 ];
+
+/// Gets the MVV-LVA (most valuable victim, least valuable attacker) score of a capture or
+/// promotion. Captures of more valuable pieces always come first, and among captures of the same
+/// piece type the ones by less valuable pieces do. Promotions count as capturing the difference
+/// between the new piece and the pawn.
+pub fn mvv_lva(board: &Board, mv: &Move) -> i32 {
+    let mut victim: i32 = match mv.get_flags() {
+        ENPASSANT => MVV_LVA_VALUES[board::PAWN],
+        _ if mv.is_capture() => MVV_LVA_VALUES[board.type_at_sq(mv.get_to()) as usize],
+        _ => 0
+    };
+    if let Some(ptype) = mv.promo_piece() {
+        victim += MVV_LVA_VALUES[ptype.index()] - MVV_LVA_VALUES[board::PAWN];
+    }
+    let attacker: usize = board.type_at_sq(mv.get_from()) as usize;
+    return victim * 8 + (board::KING - attacker) as i32;
+}
+
+/// A list of moves with a score for each of them, which hands out the moves from the highest score
+/// to the lowest. The moves are sorted lazily with a selection sort, one move per call, since a
+/// search that cuts off early only looks at the first few.
+pub struct ScoredMoves {
+    moves: MoveList,
+    scores: [i32; MAX_NUM_MOVES],
+    next: usize
+}
+
+impl ScoredMoves {
+    /// Scores every move in the list with the given function.
+    pub fn new(moves: MoveList, mut score: impl FnMut(&Move) -> i32) -> Self {
+        let mut scores: [i32; MAX_NUM_MOVES] = [0; MAX_NUM_MOVES];
+        for i in 0..moves.size() {
+            scores[i] = score(moves.at(i));
+        }
+        return ScoredMoves {
+            moves,
+            scores,
+            next: 0
+        };
+    }
+
+    /// Gets the number of moves in the list, including the ones that were already picked.
+    pub fn size(&self) -> usize {
+        return self.moves.size();
+    }
+
+    /// Gets the move with the highest score out of the ones that weren't picked yet, along with
+    /// its score.
+    pub fn pick_next(&mut self) -> Option<(Move, i32)> {
+        if self.next >= self.moves.size() {
+            return None;
+        }
+
+        let mut best: usize = self.next;
+        for i in self.next + 1..self.moves.size() {
+            if self.scores[i] > self.scores[best] {
+                best = i;
+            }
+        }
+        self.moves.swap(self.next, best);
+        self.scores.swap(self.next, best);
+        self.next += 1;
+        return Some((self.moves.at(self.next - 1).clone(), self.scores[self.next - 1]));
+    }
+}
+
+/// The killer moves: quiet moves that caused a beta cutoff at the same ply in another branch of
+/// the tree. Sibling positions are often similar enough for the same move to work again.
+pub struct Killers {
+    slots: Vec<[Option<Move>; 2]>
+}
+
+impl Killers {
+    /// Creates empty killer slots for the given number of plies.
+    pub fn new(plies: usize) -> Self {
+        return Killers {
+            slots: vec![[ None, None ]; plies]
+        };
+    }
+
+    pub fn get(&self, ply: usize) -> [Option<Move>; 2] {
+        return self.slots[ply].clone();
+    }
+
+    /// Stores a killer move at a ply. It becomes the first killer and the old first killer
+    /// becomes the second, unless the move is already the first killer.
+    pub fn store(&mut self, ply: usize, mv: &Move) {
+        let slots: &mut [Option<Move>; 2] = &mut self.slots[ply];
+        if slots[0].as_ref() != Some(mv) {
+            slots[1] = slots[0].take();
+            slots[0] = Some(mv.clone());
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.slots.fill([ None, None ]);
+    }
+}
+
+/// The butterfly history table: how often a quiet move, indexed by color and from and to squares,
+/// caused a beta cutoff, weighted by the depth of the search. Moves that failed to cut off are
+/// penalized, so the score of a move tells how good it has been anywhere in the tree.
+pub struct History {
+    table: Box<[[[i32; 64]; 64]; 2]>
+}
+
+impl History {
+    pub fn new() -> Self {
+        return History {
+            table: Box::new([[[0; 64]; 64]; 2])
+        };
+    }
+
+    pub fn get(&self, color: Color, mv: &Move) -> i32 {
+        return self.table[color as usize][mv.get_from() as usize][mv.get_to() as usize];
+    }
+
+    /// Adds a bonus (or a penalty if it is negative) to the score of a move. The closer the score
+    /// gets to MAX_HISTORY, the less a bonus moves it, so it never leaves the range and old
+    /// results fade as new ones come in.
+    pub fn update(&mut self, color: Color, mv: &Move, bonus: i32) {
+        let bonus: i32 = bonus.clamp(-MAX_HISTORY, MAX_HISTORY);
+        let entry: &mut i32 = &mut self.table[color as usize][mv.get_from() as usize]
+            [mv.get_to() as usize];
+        *entry += bonus - *entry * bonus.abs() / MAX_HISTORY;
+    }
+
+    /// Gets the bonus for a move that caused a cutoff at the given depth.
+    pub fn bonus(depth: u8) -> i32 {
+        return (depth as i32 * depth as i32).min(MAX_HISTORY);
+    }
+
+    pub fn clear(&mut self) {
+        self.table.iter_mut().flatten().for_each(|row| row.fill(0));
+    }
+}
+
+/// The countermove table: the quiet move that last refuted each move, indexed by the color, piece
+/// type and target square of the move it refuted.
+pub struct CounterMoves {
+    table: Box<[[[Option<Move>; 64]; 6]; 2]>
+}
+
+impl CounterMoves {
+    pub fn new() -> Self {
+        return CounterMoves {
+            table: Box::new(core::array::from_fn(|_| {
+                core::array::from_fn(|_| core::array::from_fn(|_| None))
+            }))
+        };
+    }
+
+    /// Gets the index of the previous move of the board, if there is one. Null moves and the
+    /// start of the history have no countermoves.
+    fn previous(board: &Board) -> Option<(usize, usize, usize)> {
+        let last: &Move = &board.history.data.last()?.last_move;
+        if board.history.data.len() <= 1 || !last.has_valid_flags() {
+            return None;
+        }
+        let to: u8 = last.get_to();
+        return Some((board.enemy_color() as usize, board.type_at_sq(to) as usize, to as usize));
+    }
+
+    /// Gets the countermove to the move that led to the position.
+    pub fn get(&self, board: &Board) -> Option<Move> {
+        let (color, ptype, to): (usize, usize, usize) = CounterMoves::previous(board)?;
+        return self.table[color][ptype][to].clone();
+    }
+
+    /// Stores a move that refuted the move that led to the position.
+    pub fn store(&mut self, board: &Board, mv: &Move) {
+        if let Some((color, ptype, to)) = CounterMoves::previous(board) {
+            self.table[color][ptype][to] = Some(mv.clone());
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.table.iter_mut().flatten().for_each(|row| row.fill(None));
+    }
+}
+
+/// Everything the search learns about which moves are good, used to order the moves of a
+/// position so that the best ones are searched first. The hash move, the captures and the
+/// killers are put first by StagedMoves, the rest of the quiet moves are scored here.
+pub struct MoveOrdering {
+    pub killers: Killers,
+    pub history: History,
+    pub countermoves: CounterMoves
+}
+
+impl MoveOrdering {
+    /// Creates empty tables, with killer slots for the given number of plies.
+    pub fn new(plies: usize) -> Self {
+        return MoveOrdering {
+            killers: Killers::new(plies),
+            history: History::new(),
+            countermoves: CounterMoves::new()
+        };
+    }
+
+    /// Scores a quiet move of the side to move: the countermove to the previous move first, then
+    /// the other moves by their history score.
+    pub fn quiet_score(&self, color: Color, countermove: Option<&Move>, mv: &Move) -> i32 {
+        return if countermove == Some(mv) {
+            COUNTER_MOVE_SCORE
+        } else {
+            self.history.get(color, mv)
+        };
+    }
+
+    /// Records a quiet move that caused a beta cutoff at some depth, along with the quiet moves
+    /// that were searched before it and didn't.
+    pub fn update_quiet(
+        &mut self,
+        board: &Board,
+        ply: usize,
+        depth: u8,
+        mv: &Move,
+        tried: &MoveList
+    ) {
+        let bonus: i32 = History::bonus(depth);
+        self.killers.store(ply, mv);
+        self.countermoves.store(board, mv);
+        self.history.update(board.turn, mv, bonus);
+        for i in 0..tried.size() {
+            self.history.update(board.turn, tried.at(i), -bonus);
+        }
+    }
+
+    /// Forgets everything, e.g. when a new game starts.
+    pub fn clear(&mut self) {
+        self.killers.clear();
+        self.history.clear();
+        self.countermoves.clear();
+    }
+}
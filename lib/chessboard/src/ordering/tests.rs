@@ -0,0 +1,153 @@
+use crate::board::*;
+use crate::generator::*;
+use crate::moves::*;
+use crate::ordering::*;
+use crate::tests::helpers::*;
+
+/// Picks every move of a scored list, checking that they come out from best to worst.
+fn picked(mut moves: ScoredMoves) -> Vec<String> {
+    let mut algbr: Vec<String> = vec![];
+    let mut last: i32 = i32::MAX;
+    while let Some((mv, score)) = moves.pick_next() {
+        assert!(score <= last);
+        last = score;
+        algbr.push(mv.to_long_algbr());
+    }
+    assert_eq!(algbr.len(), moves.size());
+    return algbr;
+}
+
+/// Collects the moves of a position in the order the search would try them.
+fn staged(
+    ordering: &MoveOrdering,
+    board: &Board,
+    ply: usize,
+    hash_move: Option<Move>,
+    countermove: Option<Move>
+) -> Vec<String> {
+    let mut staged: StagedMoves = StagedMoves::new(board, hash_move, ordering.killers.get(ply));
+    let mut algbr: Vec<String> = vec![];
+    while let Some(mv) = staged.next_move(board, |mv| {
+        ordering.quiet_score(board.turn, countermove.as_ref(), mv)
+    }) {
+        algbr.push(mv.to_long_algbr());
+    }
+    return algbr;
+}
+
+#[test]
+fn test_pick_next() {
+    let board: Board = Board::from_fen(START_FEN).unwrap();
+    let moves: MoveList = legal_moves(&board);
+    let scored: ScoredMoves = ScoredMoves::new(moves, |mv| mv.get_to() as i32);
+    let algbr: Vec<String> = picked(scored);
+    assert_eq!(algbr.len(), 20);
+    assert!(algbr[..2].iter().all(|mv| mv.ends_with("h3")));
+    assert_eq!(&algbr[18..], [ "b2b4", "a2a4" ]);
+
+    let empty: ScoredMoves = ScoredMoves::new(MoveList::new(), |_| 0);
+    assert_eq!(picked(empty), Vec::<String>::new());
+}
+
+#[test]
+fn test_mvv_lva() {
+    // The queen can be taken by a pawn or the queen, the rook by the knight and a pawn by the
+    // queen. Promotions count for the piece they gain.
+    let board: Board = Board::from_fen("4k3/P7/8/3q1r2/2P1P3/3p2N1/3Q4/4K3 w - - 0 1").unwrap();
+    let pxq: i32 = mvv_lva(&board, &mv("c4", "d5", CAPTURE));
+    let qxq: i32 = mvv_lva(&board, &mv("d2", "d5", CAPTURE));
+    let nxr: i32 = mvv_lva(&board, &mv("g3", "f5", CAPTURE));
+    let pxr: i32 = mvv_lva(&board, &mv("e4", "f5", CAPTURE));
+    let qxp: i32 = mvv_lva(&board, &mv("d2", "d3", CAPTURE));
+    let promo: i32 = mvv_lva(&board, &mv("a7", "a8", QUEEN_PROMO));
+    let underpromo: i32 = mvv_lva(&board, &mv("a7", "a8", KNIGHT_PROMO));
+    assert!(pxq > qxq && qxq > pxr && pxr > nxr && nxr > qxp);
+    assert!(promo > pxr && promo < pxq);
+    assert!(underpromo < nxr && underpromo > qxp);
+}
+
+#[test]
+fn test_killers() {
+    let mut killers: Killers = Killers::new(4);
+    let (a, b, c): (Move, Move, Move) = (mv("a2", "a3", QUIET), mv("b2", "b3", QUIET),
+        mv("c2", "c3", QUIET));
+    killers.store(1, &a);
+    killers.store(1, &a);
+    assert_eq!(killers.get(1), [ Some(a.clone()), None ]);
+    killers.store(1, &b);
+    killers.store(1, &c);
+    assert_eq!(killers.get(1), [ Some(c), Some(b) ]);
+    assert_eq!(killers.get(0), [ None, None ]);
+    killers.clear();
+    assert_eq!(killers.get(1), [ None, None ]);
+}
+
+#[test]
+fn test_history() {
+    let mut history: History = History::new();
+    let a: Move = mv("g1", "f3", QUIET);
+    history.update(Color::White, &a, History::bonus(4));
+    assert_eq!(history.get(Color::White, &a), 16);
+    assert_eq!(history.get(Color::Black, &a), 0);
+    history.update(Color::White, &a, -History::bonus(2));
+    assert_eq!(history.get(Color::White, &a), 12);
+
+    // Scores saturate instead of leaving the range.
+    for _ in 0..1000 {
+        history.update(Color::White, &a, History::bonus(60));
+    }
+    let score: i32 = history.get(Color::White, &a);
+    assert!(score > MAX_HISTORY * 9 / 10 && score <= MAX_HISTORY, "{}", score);
+    history.clear();
+    assert_eq!(history.get(Color::White, &a), 0);
+}
+
+#[test]
+fn test_countermoves() {
+    let mut countermoves: CounterMoves = CounterMoves::new();
+    let mut board: Board = Board::from_fen(START_FEN).unwrap();
+    assert_eq!(countermoves.get(&board), None);
+    let reply: Move = mv("d7", "d5", DOUBLE_PAWN_PUSH);
+    countermoves.store(&board, &reply);
+    assert_eq!(countermoves.get(&board), None);
+
+    board.make(&mv("e2", "e4", DOUBLE_PAWN_PUSH));
+    countermoves.store(&board, &reply);
+    assert_eq!(countermoves.get(&board), Some(reply.clone()));
+
+    // Any pawn move to e4 is answered the same way, but not a null move.
+    board.unmake();
+    board.make(&mv("e2", "e3", QUIET));
+    board.make(&mv("a7", "a6", QUIET));
+    board.make(&mv("e3", "e4", QUIET));
+    assert_eq!(countermoves.get(&board), Some(reply));
+    board.make_null();
+    assert_eq!(countermoves.get(&board), None);
+}
+
+#[test]
+fn test_move_ordering() {
+    let board: Board = Board::from_fen(
+        "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4"
+    ).unwrap();
+    let mut ordering: MoveOrdering = MoveOrdering::new(8);
+    let hash_move: Move = mv("e1", "g1", KING_SIDE_CASTLE);
+    ordering.killers.store(2, &mv("d2", "d3", QUIET));
+    ordering.killers.store(2, &mv("b1", "c3", QUIET));
+    let mut tried: MoveList = MoveList::new();
+    tried.push(mv("a2", "a3", QUIET));
+    ordering.update_quiet(&board, 3, 5, &mv("h2", "h3", QUIET), &tried);
+
+    let algbr: Vec<String> = staged(&ordering, &board, 2, Some(hash_move), None);
+    assert_eq!(algbr.len(), legal_moves(&board).size());
+    assert_eq!(&algbr[..6], [ "e1g1", "f3e5", "c4f7", "b1c3", "d2d3", "h2h3" ]);
+    assert_eq!(algbr.last().unwrap(), "a2a3");
+
+    // The countermove comes before the quiet moves with a better history.
+    let algbr: Vec<String> = staged(&ordering, &board, 2, None, Some(mv("a2", "a3", QUIET)));
+    assert_eq!(&algbr[2..6], [ "b1c3", "d2d3", "a2a3", "h2h3" ]);
+
+    // Evasions that capture come first.
+    let board: Board = Board::from_fen("4k3/8/8/8/8/8/3q4/4K3 w - - 0 1").unwrap();
+    assert_eq!(staged(&ordering, &board, 2, None, None)[0], "e1d2");
+}
@@ -0,0 +1,107 @@
+
+use crate::board::*;
+use crate::generator::*;
+use crate::moves::*;
+
+mod reader;
+mod writer;
+
+pub use writer::to_san;
+
+/// The result of a game as written at the end of the move text (or "*" if the game is ongoing or
+/// the result is unknown).
+pub const RESULTS: [&str; 4] = [ "1-0", "0-1", "1/2-1/2", "*" ];
+
+/// A move in a game along with the annotations that follow it.
+#[derive(Clone, Debug)]
+pub struct PgnMove {
+    pub mv: Move,
+    /// The move as it was written in the pgn (without any annotation glyphs). A "+" or "#" suffix
+    /// is kept as it was written, even if it is missing or wrong, since it isn't needed to find
+    /// the move.
+    pub san: String,
+    /// Numeric annotation glyphs, both the "$n" form and the "!?" style suffixes.
+    pub nags: Vec<u8>,
+    /// Comments written after the move, with runs of whitespace collapsed to single spaces.
+    pub comments: Vec<String>,
+    /// Alternatives to this move, each one starting from the position before it was played.
+    pub variations: Vec<PgnLine>,
+}
+
+/// A sequence of moves. The mainline of a game and each of its variations are lines.
+#[derive(Clone, Debug, Default)]
+pub struct PgnLine {
+    /// Comments written before the first move of the line.
+    pub comments: Vec<String>,
+    pub moves: Vec<PgnMove>,
+}
+
+/// A game read from a pgn string.
+pub struct Game {
+    /// Tag pairs in the order that they appeared.
+    pub tags: Vec<(String, String)>,
+    /// Fen of the starting position, taken from the FEN tag if there is one.
+    pub start_fen: String,
+    pub mainline: PgnLine,
+    /// Result token at the end of the move text.
+    pub result: String,
+    /// The position at the end of the mainline.
+    pub board: Board,
+}
+
+impl Game {
+    /// Parses a single game from a pgn string. Anything other than whitespace after the game is
+    /// treated as an error, use read_games for files that hold multiple games.
+    pub fn from_pgn(pgn: &str) -> Result<Game, PgnError<'_>> {
+        let mut parser = reader::Parser::new(pgn);
+        let game: Game = parser.parse_game()?;
+        parser.expect_end()?;
+        return Ok(game);
+    }
+
+    /// Creates a game without tags or moves starting from the given position.
+    pub fn from_fen(fen: &str) -> Result<Game, FenError> {
+        let board: Board = Board::from_fen(fen)?;
+        return Ok(Game {
+            tags: vec![],
+            start_fen: fen.to_string(),
+            mainline: PgnLine::default(),
+            result: "*".to_string(),
+            board
+        });
+    }
+
+    /// Plays a legal move at the end of the mainline.
+    pub fn push(&mut self, mv: Move) {
+        let generator: MoveGenerator = MoveGenerator::new();
+        let san: String = to_san(&generator, &mut self.board, &mv);
+        self.board.make(&mv);
+        self.mainline.moves.push(PgnMove {
+            mv,
+            san,
+            nags: vec![],
+            comments: vec![],
+            variations: vec![]
+        });
+    }
+
+    /// Gets the value of a tag, if the game has it.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        return self.tags.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str());
+    }
+
+    /// Gets the moves of the mainline without any annotations.
+    pub fn mainline_moves(&self) -> Vec<Move> {
+        return self.mainline.moves.iter().map(|pgn_move| pgn_move.mv.clone()).collect();
+    }
+}
+
+/// Parses every game in a pgn string.
+pub fn read_games(pgn: &str) -> Result<Vec<Game>, PgnError<'_>> {
+    let mut parser = reader::Parser::new(pgn);
+    let mut games: Vec<Game> = vec![];
+    while !parser.at_end()? {
+        games.push(parser.parse_game()?);
+    }
+    return Ok(games);
+}
@@ -0,0 +1,364 @@
+
+use crate::board::*;
+use crate::generator::*;
+use crate::moves::*;
+use crate::pgn::*;
+
+#[derive(Clone, Debug, PartialEq)]
+enum TokenKind<'a> {
+    TagOpen,
+    TagClose,
+    Str(String),
+    Symbol(&'a str),
+    MoveNumber,
+    Nag(u8),
+    Comment(&'a str),
+    VariationOpen,
+    VariationClose,
+    Result(&'a str),
+    End,
+}
+
+struct Token<'a> {
+    kind: TokenKind<'a>,
+    line: usize,
+    col: usize
+}
+
+/// Splits a pgn string into tokens while keeping track of the line and column of each one.
+struct Lexer<'a> {
+    text: &'a str,
+    pos: usize,
+    line: usize,
+    col: usize
+}
+
+fn malformed(msg: &str, line: usize, col: usize) -> PgnError<'_> {
+    return PgnError::PgnMalformedError(msg).at(line, col);
+}
+
+/// Converts the traditional move suffix annotations to their numeric annotation glyph.
+fn glyph_to_nag(glyph: &str) -> Option<u8> {
+    return match glyph {
+        "!" => Some(1),
+        "?" => Some(2),
+        "!!" => Some(3),
+        "??" => Some(4),
+        "!?" => Some(5),
+        "?!" => Some(6),
+        _ => None
+    };
+}
+
+fn is_symbol_char(c: char) -> bool {
+    return c.is_ascii_alphanumeric() || "_+#=:-/".contains(c);
+}
+
+impl<'a> Lexer<'a> {
+    fn new(text: &'a str) -> Self {
+        Lexer {
+            text,
+            pos: 0,
+            line: 1,
+            col: 1
+        }
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        return self.text[self.pos..].chars().next();
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c: char = self.peek_char()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.col = 1;
+        } else {
+            self.col += 1;
+        }
+        return Some(c);
+    }
+
+    /// Consumes characters while the predicate holds, returning the slice that was consumed.
+    fn take_while(&mut self, pred: impl Fn(char) -> bool) -> &'a str {
+        let start: usize = self.pos;
+        while self.peek_char().is_some_and(&pred) {
+            self.bump();
+        }
+        return &self.text[start..self.pos];
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            match self.peek_char() {
+                // Lines starting with '%' are escaped and ignored entirely.
+                Some('%') if self.col == 1 => { self.take_while(|c| c != '\n'); },
+                // Stray periods (e.g. "1 . e4") carry no information.
+                Some(c) if c.is_whitespace() || c == '.' => { self.bump(); },
+                _ => return
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Token<'a>, PgnError<'a>> {
+        self.skip_whitespace();
+        let (line, col): (usize, usize) = (self.line, self.col);
+        let token = |kind: TokenKind<'a>| Ok(Token { kind, line, col });
+
+        let c: char = match self.peek_char() {
+            Some(c) => c,
+            None => return token(TokenKind::End)
+        };
+
+        match c {
+            '[' => { self.bump(); token(TokenKind::TagOpen) },
+            ']' => { self.bump(); token(TokenKind::TagClose) },
+            '(' => { self.bump(); token(TokenKind::VariationOpen) },
+            ')' => { self.bump(); token(TokenKind::VariationClose) },
+            '*' => { self.bump(); token(TokenKind::Result("*")) },
+            '"' => {
+                self.bump();
+                let mut value: String = String::new();
+                loop {
+                    match self.bump() {
+                        Some('"') => break,
+                        Some('\\') => match self.bump() {
+                            Some(escaped) => value.push(escaped),
+                            None => return Err(malformed("unterminated string", line, col))
+                        },
+                        Some(c) => value.push(c),
+                        None => return Err(malformed("unterminated string", line, col))
+                    }
+                }
+                token(TokenKind::Str(value))
+            },
+            '{' => {
+                self.bump();
+                let text: &'a str = self.take_while(|c| c != '}');
+                if self.bump().is_none() {
+                    return Err(malformed("unterminated comment", line, col));
+                }
+                token(TokenKind::Comment(text.trim()))
+            },
+            ';' => {
+                self.bump();
+                let text: &'a str = self.take_while(|c| c != '\n');
+                token(TokenKind::Comment(text.trim()))
+            },
+            '$' => {
+                self.bump();
+                let digits: &'a str = self.take_while(|c| c.is_ascii_digit());
+                match digits.parse::<u8>() {
+                    Ok(nag) => token(TokenKind::Nag(nag)),
+                    Err(_) => Err(malformed("invalid numeric annotation glyph", line, col))
+                }
+            },
+            '!' | '?' => {
+                let glyph: &'a str = self.take_while(|c| c == '!' || c == '?');
+                match glyph_to_nag(glyph) {
+                    Some(nag) => token(TokenKind::Nag(nag)),
+                    None => Err(malformed(glyph, line, col))
+                }
+            },
+            c if is_symbol_char(c) => {
+                let symbol: &'a str = self.take_while(is_symbol_char);
+                if RESULTS.contains(&symbol) {
+                    return token(TokenKind::Result(symbol));
+                }
+                if symbol.chars().all(|c| c.is_ascii_digit()) && self.peek_char() == Some('.') {
+                    self.take_while(|c| c == '.');
+                    return token(TokenKind::MoveNumber);
+                }
+                token(TokenKind::Symbol(symbol))
+            },
+            _ => {
+                let end: usize = self.pos + c.len_utf8();
+                Err(malformed(&self.text[self.pos..end], line, col))
+            }
+        }
+    }
+}
+
+/// Recursive descent parser over the tokens of a pgn string.
+pub struct Parser<'a> {
+    lexer: Lexer<'a>,
+    peeked: Option<Token<'a>>,
+    generator: MoveGenerator,
+    fen_location: (usize, usize)
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Parser {
+            lexer: Lexer::new(text),
+            peeked: None,
+            generator: MoveGenerator::new(),
+            fen_location: (1, 1)
+        }
+    }
+
+    fn peek(&mut self) -> Result<&Token<'a>, PgnError<'a>> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.lexer.next_token()?);
+        }
+        return Ok(self.peeked.as_ref().unwrap());
+    }
+
+    fn next(&mut self) -> Result<Token<'a>, PgnError<'a>> {
+        return match self.peeked.take() {
+            Some(token) => Ok(token),
+            None => self.lexer.next_token()
+        };
+    }
+
+    /// Returns true if there is nothing but whitespace left to parse.
+    pub fn at_end(&mut self) -> Result<bool, PgnError<'a>> {
+        return Ok(self.peek()?.kind == TokenKind::End);
+    }
+
+    pub fn expect_end(&mut self) -> Result<(), PgnError<'a>> {
+        let token: Token<'a> = self.next()?;
+        if token.kind != TokenKind::End {
+            return Err(malformed("expected a single game", token.line, token.col));
+        }
+        return Ok(());
+    }
+
+    pub fn parse_game(&mut self) -> Result<Game, PgnError<'a>> {
+        let tags: Vec<(String, String)> = self.parse_tags()?;
+
+        // Games that don't start from the initial position give it in the FEN tag.
+        let start_fen: String = tags.iter()
+            .find(|(name, _)| name == "FEN")
+            .map_or(START_FEN.to_string(), |(_, fen)| fen.clone());
+        let mut board: Board = Board::from_fen(&start_fen)
+            .map_err(|err| PgnError::PgnFenError(err).at(self.fen_location.0, self.fen_location.1))?;
+
+        let mainline: PgnLine = self.parse_line(&mut board)?;
+
+        let token: Token<'a> = self.next()?;
+        let result: String = match token.kind {
+            TokenKind::Result(result) => result.to_string(),
+            TokenKind::End => "*".to_string(),
+            TokenKind::TagOpen => {
+                // The next game started without this one giving a result.
+                self.peeked = Some(token);
+                "*".to_string()
+            },
+            _ => return Err(malformed("unexpected ')'", token.line, token.col))
+        };
+
+        return Ok(Game {
+            tags,
+            start_fen,
+            mainline,
+            result,
+            board
+        });
+    }
+
+    fn parse_tags(&mut self) -> Result<Vec<(String, String)>, PgnError<'a>> {
+        let mut tags: Vec<(String, String)> = vec![];
+        while self.peek()?.kind == TokenKind::TagOpen {
+            let open: Token<'a> = self.next()?;
+            let name: Token<'a> = self.next()?;
+            let value: Token<'a> = self.next()?;
+            let close: Token<'a> = self.next()?;
+
+            match (name.kind, value.kind, close.kind) {
+                (TokenKind::Symbol(name), TokenKind::Str(value), TokenKind::TagClose) => {
+                    if name == "FEN" {
+                        self.fen_location = (open.line, open.col);
+                    }
+                    tags.push((name.to_string(), value));
+                },
+                _ => return Err(malformed("malformed tag pair", open.line, open.col))
+            }
+        }
+        return Ok(tags);
+    }
+
+    /// Parses moves and their annotations until the end of the current line. The moves of the
+    /// line are left made on the board.
+    fn parse_line(&mut self, board: &mut Board) -> Result<PgnLine, PgnError<'a>> {
+        let mut line: PgnLine = PgnLine::default();
+
+        loop {
+            let kind: TokenKind<'a> = self.peek()?.kind.clone();
+            match kind {
+                TokenKind::MoveNumber => {
+                    self.next()?;
+                },
+                TokenKind::Symbol(san) => {
+                    let token: Token<'a> = self.next()?;
+                    let mv: Move = self.resolve(san, board)
+                        .map_err(|err| err.at(token.line, token.col))?;
+                    board.make(&mv);
+                    line.moves.push(PgnMove {
+                        mv,
+                        san: san.to_string(),
+                        nags: vec![],
+                        comments: vec![],
+                        variations: vec![]
+                    });
+                },
+                TokenKind::Nag(nag) => {
+                    let token: Token<'a> = self.next()?;
+                    match line.moves.last_mut() {
+                        Some(last) => last.nags.push(nag),
+                        None => return Err(malformed(
+                            "annotation before the first move", token.line, token.col
+                        ))
+                    }
+                },
+                TokenKind::Comment(text) => {
+                    self.next()?;
+                    // Line breaks inside comments are only there to keep lines short.
+                    let text: String = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+                    match line.moves.last_mut() {
+                        Some(last) => last.comments.push(text),
+                        None => line.comments.push(text)
+                    }
+                },
+                TokenKind::VariationOpen => {
+                    let open: Token<'a> = self.next()?;
+                    let last: Move = match line.moves.last() {
+                        Some(last) => last.mv.clone(),
+                        None => return Err(malformed(
+                            "variation before the first move", open.line, open.col
+                        ))
+                    };
+
+                    // A variation replaces the last move, so it starts from the position before.
+                    board.unmake();
+                    let variation: PgnLine = self.parse_line(board)?;
+                    if self.next()?.kind != TokenKind::VariationClose {
+                        return Err(malformed("unterminated variation", open.line, open.col));
+                    }
+                    for _ in &variation.moves {
+                        board.unmake();
+                    }
+                    board.make(&last);
+
+                    line.moves.last_mut().unwrap().variations.push(variation);
+                },
+                TokenKind::VariationClose | TokenKind::Result(_)
+                    | TokenKind::End | TokenKind::TagOpen => {
+                    return Ok(line);
+                },
+                TokenKind::TagClose | TokenKind::Str(_) => {
+                    let token: Token<'a> = self.next()?;
+                    return Err(malformed("unexpected token in move text", token.line, token.col));
+                }
+            }
+        }
+    }
+
+    fn resolve(&self, san: &'a str, board: &Board) -> Result<Move, PgnError<'a>> {
+        let mut moves: MoveList = MoveList::new();
+        let state: BoardTables = self.generator.gen_board_tables(board);
+        self.generator.gen_moves(&mut moves, board, &state);
+        return Move::from_short_algbr(san, board, &moves);
+    }
+}
@@ -0,0 +1,153 @@
+
+use crate::board::*;
+use crate::generator::*;
+use crate::moves::*;
+use crate::pgn::*;
+
+/// Export format lines may be no longer than this.
+const MAX_LINE_LEN: usize = 80;
+
+/// Returns the standard algebraic notation for a move, including the "+" or "#" suffix if it
+/// gives check or mate. The board is left as it was.
+pub fn to_san(generator: &MoveGenerator, board: &mut Board, mv: &Move) -> String {
+    let mut moves: MoveList = MoveList::new();
+    let state: BoardTables = generator.gen_board_tables(board);
+    generator.gen_moves(&mut moves, board, &state);
+    let mut san: String = mv.to_short_algbr(board, &moves);
+
+    board.make(mv);
+    let state: BoardTables = generator.gen_board_tables(board);
+    if state.checks != 0 {
+        generator.gen_moves(&mut moves, board, &state);
+        san.push(if moves.size() == 0 { '#' } else { '+' });
+    }
+    board.unmake();
+
+    return san;
+}
+
+/// Collects the tokens of the move text so that they can be wrapped into lines at the end.
+struct Writer {
+    generator: MoveGenerator,
+    tokens: Vec<String>
+}
+
+fn escape(value: &str) -> String {
+    return value.replace('\\', "\\\\").replace('"', "\\\"");
+}
+
+impl Writer {
+    fn new() -> Self {
+        Writer {
+            generator: MoveGenerator::new(),
+            tokens: vec![]
+        }
+    }
+
+    /// Surrounds the tokens pushed since start with the given delimiters, without spaces between
+    /// the delimiters and the tokens.
+    fn enclose(&mut self, start: usize, open: char, close: char) {
+        if self.tokens.len() == start {
+            self.tokens.push(format!("{}{}", open, close));
+            return;
+        }
+        self.tokens[start].insert(0, open);
+        self.tokens.last_mut().unwrap().push(close);
+    }
+
+    /// Comments are split into words so that long comments wrap like the rest of the move text.
+    /// A comment can't contain its closing brace, so any "}" in it is dropped.
+    fn push_comment(&mut self, comment: &str) {
+        let start: usize = self.tokens.len();
+        let comment: String = comment.replace('}', "");
+        self.tokens.extend(comment.split_whitespace().map(|word| word.to_string()));
+        self.enclose(start, '{', '}');
+    }
+
+    /// Writes the moves of a line from the given position, leaving the board as it was.
+    fn write_line(&mut self, line: &PgnLine, board: &mut Board) {
+        for comment in &line.comments {
+            self.push_comment(comment);
+        }
+
+        // Black moves only get a number at the start of a line or after an interruption.
+        let mut needs_number: bool = true;
+        for pgn_move in &line.moves {
+            if board.turn as usize == WHITE {
+                self.tokens.push(format!("{}.", board.fullmv_num));
+            } else if needs_number {
+                self.tokens.push(format!("{}...", board.fullmv_num));
+            }
+
+            let san: String = to_san(&self.generator, board, &pgn_move.mv);
+            self.tokens.push(san);
+            for nag in &pgn_move.nags {
+                self.tokens.push(format!("${}", nag));
+            }
+            for comment in &pgn_move.comments {
+                self.push_comment(comment);
+            }
+
+            // Variations are alternatives to this move, so they start from the position before it.
+            for variation in &pgn_move.variations {
+                let start: usize = self.tokens.len();
+                self.write_line(variation, board);
+                self.enclose(start, '(', ')');
+            }
+            needs_number = !pgn_move.comments.is_empty() || !pgn_move.variations.is_empty();
+
+            board.make(&pgn_move.mv);
+        }
+
+        for _ in &line.moves {
+            board.unmake();
+        }
+    }
+
+    fn finish(self) -> String {
+        let mut text: String = String::new();
+        let mut line_len: usize = 0;
+        for token in &self.tokens {
+            if line_len == 0 {
+                line_len = token.len();
+            } else if line_len + 1 + token.len() > MAX_LINE_LEN {
+                text.push('\n');
+                line_len = token.len();
+            } else {
+                text.push(' ');
+                line_len += 1 + token.len();
+            }
+            text.push_str(token);
+        }
+        return text;
+    }
+}
+
+impl Game {
+    /// Writes the game in pgn export format. Moves are written in standard algebraic notation
+    /// and the move text is wrapped at 80 columns.
+    pub fn to_pgn(&self) -> String {
+        let mut pgn: String = String::new();
+
+        let mut tags: Vec<(String, String)> = self.tags.clone();
+        if self.start_fen != START_FEN && self.tag("FEN").is_none() {
+            tags.push(("SetUp".to_string(), "1".to_string()));
+            tags.push(("FEN".to_string(), self.start_fen.clone()));
+        }
+        for (name, value) in &tags {
+            pgn.push_str(&format!("[{} \"{}\"]\n", name, escape(value)));
+        }
+        if !tags.is_empty() {
+            pgn.push('\n');
+        }
+
+        let mut board: Board = Board::from_fen(&self.start_fen).unwrap();
+        let mut writer: Writer = Writer::new();
+        writer.write_line(&self.mainline, &mut board);
+        writer.tokens.push(self.result.clone());
+        pgn.push_str(&writer.finish());
+        pgn.push('\n');
+
+        return pgn;
+    }
+}
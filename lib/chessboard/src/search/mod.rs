@@ -0,0 +1,837 @@
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::board::*;
+use crate::eval::Evaluator;
+use crate::generator::*;
+use crate::moves::*;
+use crate::ordering::*;
+use crate::pgn;
+
+mod time;
+mod tt;
+pub use time::*;
+pub use tt::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Score of a checkmate delivered right now. Mates further away score less, so that the search
+/// prefers the quickest mate and the slowest way of getting mated.
+pub const MATE: i32 = 32000;
+/// Bound that is outside of every possible score.
+pub const INFINITY: i32 = 32001;
+/// The search never goes deeper than this many plies from the root.
+pub const MAX_PLY: usize = 128;
+
+/// Reverse futility pruning is used up to this depth.
+const RFP_DEPTH: u8 = 6;
+/// How far above beta the static evaluation has to be per ply of depth for reverse futility
+/// pruning.
+const RFP_MARGIN: i32 = 80;
+/// Null move pruning is used from this depth on.
+const NULL_MOVE_DEPTH: u8 = 3;
+/// How far below alpha the static evaluation has to be for futility pruning, indexed by depth.
+const FUTILITY_MARGINS: [i32; 4] = [ 0, 200, 350, 500 ];
+/// Late move reductions are used from this depth on, for the moves after the first few.
+const LMR_DEPTH: u8 = 3;
+const LMR_MOVES: usize = 3;
+/// How many nodes are searched between checks of the stop flag and the clock.
+const STOP_CHECK_INTERVAL: u64 = 1024;
+/// An iteration that scores this much below the previous one counts as failing low.
+const FAIL_LOW_MARGIN: i32 = 30;
+/// Half the width of the first aspiration window around the score of the previous iteration.
+const ASPIRATION_WINDOW: i32 = 25;
+
+/// Turns the parts of the search that trade exactness for speed on or off, e.g. to measure what
+/// each of them is worth. Everything is on by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SearchConfig {
+    /// Skip nodes where passing the turn still fails high with a reduced search.
+    pub null_move: bool,
+    /// Search quiet moves that come late in the move ordering shallower.
+    pub late_move_reductions: bool,
+    /// Skip quiet moves near the leaves when the evaluation is far below alpha.
+    pub futility: bool,
+    /// Skip nodes near the leaves when the evaluation is far above beta.
+    pub reverse_futility: bool,
+    /// Search moves that give check a ply deeper.
+    pub check_extensions: bool,
+    /// Start each iteration with a narrow window around the previous score.
+    pub aspiration_windows: bool
+}
+
+impl SearchConfig {
+    /// A config with everything turned off, which gives a plain alpha-beta search.
+    pub const fn plain() -> Self {
+        return SearchConfig {
+            null_move: false,
+            late_move_reductions: false,
+            futility: false,
+            reverse_futility: false,
+            check_extensions: false,
+            aspiration_windows: false
+        };
+    }
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        return SearchConfig {
+            null_move: true,
+            late_move_reductions: true,
+            futility: true,
+            reverse_futility: true,
+            check_extensions: true,
+            aspiration_windows: true
+        };
+    }
+}
+
+/// Gets the score of giving mate in the given number of plies from the root.
+pub fn mate_in(ply: usize) -> i32 {
+    return MATE - ply as i32;
+}
+
+/// Gets the score of being mated in the given number of plies from the root.
+pub fn mated_in(ply: usize) -> i32 {
+    return -MATE + ply as i32;
+}
+
+/// Returns true if the score is a forced mate for either side.
+pub fn is_mate_score(score: i32) -> bool {
+    return score.abs() >= MATE - MAX_PLY as i32;
+}
+
+/// Gets the number of moves (not plies) until mate for a mate score, negative if the side to
+/// move is getting mated. This is how UCI reports mates.
+pub fn mate_distance(score: i32) -> i32 {
+    return if score > 0 {
+        (MATE - score + 1) / 2
+    } else {
+        -(MATE + score) / 2
+    };
+}
+
+/// The outcome of searching a position to some depth.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchResult {
+    /// None only if there are no legal moves in the position.
+    pub best_move: Option<Move>,
+    /// Score from the perspective of the side to move.
+    pub score: i32,
+    pub depth: u8,
+    /// The deepest ply the search reached, including quiescence search.
+    pub seldepth: u8,
+    /// The principal variation, the line both sides are expected to play, starting with the best
+    /// move.
+    pub pv: Vec<Move>,
+    /// The best lines with different first moves, from best to worst, see Searcher::set_multipv.
+    /// The first line is the same as the fields above.
+    pub lines: Vec<PvLine>,
+    pub nodes: u64,
+    /// Time spent searching.
+    pub time: Duration,
+    /// How full the transposition table is in permille, see TranspositionTable::hashfull.
+    pub hashfull: usize
+}
+
+/// One of the lines of a MultiPV search.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PvLine {
+    /// Score from the perspective of the side to move.
+    pub score: i32,
+    pub depth: u8,
+    pub seldepth: u8,
+    pub pv: Vec<Move>
+}
+
+impl PvLine {
+    /// Gets the moves of the line in long algebraic notation as used by UCI, separated by
+    /// spaces.
+    pub fn to_uci(&self) -> String {
+        return self.pv.iter().map(|mv| mv.to_long_algbr()).collect::<Vec<String>>().join(" ");
+    }
+
+    /// Gets the moves of the line in standard algebraic notation, separated by spaces. The board
+    /// has to be the position the line starts from, and is left as it was.
+    pub fn to_san(&self, generator: &MoveGenerator, board: &mut Board) -> String {
+        let mut moves: Vec<String> = vec![];
+        for mv in &self.pv {
+            moves.push(pgn::to_san(generator, board, mv));
+            board.make(mv);
+        }
+        for _ in &self.pv {
+            board.unmake();
+        }
+        return moves.join(" ");
+    }
+}
+
+/// Lazy SMP helper threads skip some iterations of iterative deepening, so that they spread out
+/// over different depths. Helper i skips a depth if (depth + SKIP_PHASE[i]) / SKIP_SIZE[i] is odd.
+const SKIP_SIZE: [u8; 20] = [ 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 4, 4, 4, 4, 4, 4, 4, 4 ];
+const SKIP_PHASE: [u8; 20] = [ 0, 1, 0, 1, 2, 3, 0, 1, 2, 3, 4, 5, 0, 1, 2, 3, 4, 5, 6, 7 ];
+
+/// Searches positions for the best move. Keeps the state that is reused between searches, such
+/// as the transposition table and the move ordering and pawn structure tables of every thread.
+///
+/// With more than one thread the search uses Lazy SMP: the helper threads search the same
+/// position on their own copies of the board at the same time as the main thread, and share what
+/// they find through the transposition table. The main thread reports the progress and decides
+/// when the search is done.
+pub struct Searcher {
+    pub config: SearchConfig,
+    tt: Arc<TranspositionTable>,
+    stop: Arc<AtomicBool>,
+    main: Worker,
+    helpers: Vec<Worker>,
+    multipv: usize
+}
+
+impl Searcher {
+    pub fn new() -> Self {
+        let tt: Arc<TranspositionTable> = Arc::new(TranspositionTable::new(DEFAULT_SIZE_MB));
+        let stop: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        Searcher {
+            config: SearchConfig::default(),
+            main: Worker::new(tt.clone(), stop.clone()),
+            tt,
+            stop,
+            helpers: vec![],
+            multipv: 1
+        }
+    }
+
+    /// Replaces the transposition table with an empty one of the given size in MB.
+    pub fn set_hash_size(&mut self, mb: usize) {
+        let tt: Arc<TranspositionTable> = Arc::new(TranspositionTable::new(mb));
+        for worker in self.workers_mut() {
+            worker.tt = tt.clone();
+        }
+        self.tt = tt;
+    }
+
+    pub fn tt(&self) -> &TranspositionTable {
+        return &self.tt;
+    }
+
+    /// Sets the number of threads that search, including the main thread. There is always at
+    /// least one.
+    pub fn set_threads(&mut self, threads: usize) {
+        let helpers: usize = threads.max(1) - 1;
+        self.helpers.truncate(helpers);
+        while self.helpers.len() < helpers {
+            self.helpers.push(Worker::new(self.tt.clone(), self.stop.clone()));
+        }
+    }
+
+    pub fn threads(&self) -> usize {
+        return self.helpers.len() + 1;
+    }
+
+    /// Sets the number of lines to search, each starting with a different move. Searching more
+    /// than one line is slower, as every line is searched to the full depth. There is always at
+    /// least one.
+    pub fn set_multipv(&mut self, lines: usize) {
+        self.multipv = lines.max(1);
+    }
+
+    pub fn multipv(&self) -> usize {
+        return self.multipv;
+    }
+
+    fn workers_mut(&mut self) -> impl Iterator<Item = &mut Worker> {
+        return std::iter::once(&mut self.main).chain(self.helpers.iter_mut());
+    }
+
+    /// Forgets everything learned in earlier searches, e.g. when a new game starts.
+    pub fn clear(&mut self) {
+        self.tt.clear();
+        for worker in self.workers_mut() {
+            worker.ordering.clear();
+            worker.evaluator.clear();
+        }
+    }
+
+    /// Searches the position with iterative deepening up to the given depth and returns the
+    /// result of the deepest iteration. The board is left as it was.
+    pub fn search(&mut self, board: &mut Board, depth: u8) -> SearchResult {
+        return self.search_with(board, depth, |_| {});
+    }
+
+    /// Like search, but calls the callback with the result of every iteration, e.g. to report
+    /// progress.
+    pub fn search_with(
+        &mut self,
+        board: &mut Board,
+        depth: u8,
+        on_iteration: impl FnMut(&SearchResult)
+    ) -> SearchResult {
+        return self.search_limits(board, &SearchLimits::depth(depth), on_iteration);
+    }
+
+    /// Gets the flag that stops the search when it is set. It can be set from another thread
+    /// while this one is searching, and it is cleared when a search starts.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        return self.stop.clone();
+    }
+
+    /// Searches the position with iterative deepening until one of the limits is reached or the
+    /// search is stopped, calling the callback with the result of every iteration of the main
+    /// thread that finished. The node counts include every thread.
+    ///
+    /// Returns the result of the deepest iteration that finished on any thread. The first
+    /// iteration always finishes, so there is a best move unless there are no legal moves.
+    pub fn search_limits(
+        &mut self,
+        board: &mut Board,
+        limits: &SearchLimits,
+        on_iteration: impl FnMut(&SearchResult)
+    ) -> SearchResult {
+        self.stop.store(false, Ordering::Relaxed);
+        self.tt.new_search();
+        let nodes: Arc<AtomicU64> = Arc::new(AtomicU64::new(0));
+        let config: SearchConfig = self.config;
+        for worker in self.workers_mut() {
+            worker.reset(config, limits, board.turn, nodes.clone());
+        }
+        // Only the main thread searches more than one line, the helpers fill the table.
+        self.main.multipv = self.multipv;
+        let multipv: usize = self.multipv;
+
+        let (main, helpers): (&mut Worker, &mut Vec<Worker>) = (&mut self.main, &mut self.helpers);
+        let stop: &AtomicBool = &self.stop;
+        let mut result: SearchResult = thread::scope(|scope| {
+            let handles: Vec<_> = helpers.iter_mut().enumerate().map(|(i, helper)| {
+                let mut board: Board = board.clone();
+                scope.spawn(move || helper.iterate(&mut board, Some(i), |_| {}))
+            }).collect();
+
+            let mut result: SearchResult = main.iterate(board, None, on_iteration);
+            stop.store(true, Ordering::Relaxed);
+
+            // A helper may have finished a deeper iteration than the main thread, but it only has
+            // the best line.
+            for handle in handles {
+                let helper: SearchResult = handle.join().unwrap();
+                if multipv == 1 && helper.depth > result.depth && helper.best_move.is_some() {
+                    result = helper;
+                }
+            }
+            return result;
+        });
+
+        result.nodes = nodes.load(Ordering::Relaxed);
+        result.time = self.main.time.elapsed();
+        return result;
+    }
+}
+
+/// Returns true if the position is drawn no matter what is played from here. Repeating a
+/// position once is enough, as whatever worked the first time can be repeated.
+fn is_draw(board: &Board, ply: usize) -> bool {
+    return ply > 0 && (board.is_repetition(2) || board.is_fifty_move_draw()
+        || board.is_insufficient_material());
+}
+
+/// Returns true if the side to move has a piece other than pawns and the king. Without one,
+/// zugzwang is common enough that passing can't be trusted to be the worst move.
+fn has_non_pawn_material(board: &Board) -> bool {
+    let pieces: &[u64; 6] = &board.bitboard.piece[board.turn as usize];
+    return pieces[KNIGHT] | pieces[BISHOP] | pieces[ROOK] | pieces[QUEEN] != 0;
+}
+
+/// The state of one searching thread.
+struct Worker {
+    config: SearchConfig,
+    generator: MoveGenerator,
+    evaluator: Evaluator,
+    tt: Arc<TranspositionTable>,
+    ordering: MoveOrdering,
+    limits: SearchLimits,
+    time: TimeManager,
+    /// Set from any thread to stop the search, see Searcher::stop_flag.
+    stop: Arc<AtomicBool>,
+    /// Set once the search has stopped because of the stop flag or a limit. Everything searched
+    /// after it is set is thrown away.
+    stopped: bool,
+    /// The depth of the current iteration of iterative deepening.
+    iteration: u8,
+    /// Nodes searched by this thread. They are added to the shared count of all threads every
+    /// STOP_CHECK_INTERVAL nodes.
+    nodes: u64,
+    shared_nodes: Arc<AtomicU64>,
+    /// Triangular table of principal variations, pv[ply] is the best line found from that ply.
+    pv: Vec<Vec<Move>>,
+    /// The deepest ply reached in the current line.
+    seldepth: usize,
+    /// The number of lines to search.
+    multipv: usize,
+    /// Root moves that aren't searched, because they start lines that were already found.
+    excluded: Vec<Move>
+}
+
+impl Worker {
+    fn new(tt: Arc<TranspositionTable>, stop: Arc<AtomicBool>) -> Self {
+        Worker {
+            config: SearchConfig::default(),
+            generator: MoveGenerator::new(),
+            evaluator: Evaluator::new(),
+            tt,
+            ordering: MoveOrdering::new(MAX_PLY + 1),
+            limits: SearchLimits::default(),
+            time: TimeManager::new(&SearchLimits::default(), Color::White),
+            stop,
+            stopped: false,
+            iteration: 0,
+            nodes: 0,
+            shared_nodes: Arc::new(AtomicU64::new(0)),
+            pv: vec![vec![]; MAX_PLY + 1],
+            seldepth: 0,
+            multipv: 1,
+            excluded: vec![]
+        }
+    }
+
+    /// Prepares the worker for a new search. A search for a mate doesn't prune or reduce, since
+    /// that could hide the mate at the depth it should be found at.
+    fn reset(
+        &mut self,
+        config: SearchConfig,
+        limits: &SearchLimits,
+        turn: Color,
+        shared_nodes: Arc<AtomicU64>
+    ) {
+        self.config = if limits.mate.is_some() {
+            SearchConfig {
+                null_move: false,
+                late_move_reductions: false,
+                futility: false,
+                reverse_futility: false,
+                ..config
+            }
+        } else {
+            config
+        };
+        self.limits = limits.clone();
+        self.time = TimeManager::new(limits, turn);
+        self.stopped = false;
+        self.nodes = 0;
+        self.shared_nodes = shared_nodes;
+    }
+
+    /// Gets the number of nodes searched by all threads so far.
+    fn total_nodes(&self) -> u64 {
+        return self.shared_nodes.load(Ordering::Relaxed) + self.nodes % STOP_CHECK_INTERVAL;
+    }
+
+    /// Runs iterative deepening, see Searcher::search_limits. Helper threads are numbered from 0
+    /// and skip some of the depths, the main thread is not numbered.
+    fn iterate(
+        &mut self,
+        board: &mut Board,
+        helper: Option<usize>,
+        mut on_iteration: impl FnMut(&SearchResult)
+    ) -> SearchResult {
+        let limits: SearchLimits = self.limits.clone();
+        let mut result: SearchResult = SearchResult {
+            best_move: None,
+            score: 0,
+            depth: 0,
+            seldepth: 0,
+            pv: vec![],
+            lines: vec![],
+            nodes: 0,
+            time: Duration::ZERO,
+            hashfull: 0
+        };
+
+        // A mate in n moves is 2n - 1 plies away, but that isn't a depth limit: extensions and
+        // the transposition table can bring it in later, so the search goes on until it is found.
+        let max_depth: u8 = limits.depth.map_or(MAX_PLY as u8, |depth| depth.max(1));
+
+        for iteration in 1..=max_depth {
+            if let Some(i) = helper {
+                let (size, phase): (u8, u8) = (SKIP_SIZE[i % 20], SKIP_PHASE[i % 20]);
+                if iteration > 1 && ((iteration + phase) / size) % 2 == 1 {
+                    continue;
+                }
+            }
+
+            self.iteration = iteration;
+            let (lines, score, failed_low): (Vec<PvLine>, i32, bool) = self.search_lines(board,
+                &result);
+            if self.stopped {
+                break;
+            }
+
+            let pv: Vec<Move> = lines.first().map_or(vec![], |line| line.pv.clone());
+            let best_move: Option<Move> = pv.first().cloned();
+            let best_move_changed: bool = iteration > 1 && best_move != result.best_move;
+            let dropped: bool = iteration > 1 && score < result.score - FAIL_LOW_MARGIN;
+            self.time.update(best_move_changed, failed_low || dropped);
+            result = SearchResult {
+                best_move,
+                score,
+                depth: iteration,
+                seldepth: lines.first().map_or(0, |line| line.seldepth),
+                pv,
+                lines,
+                nodes: self.total_nodes(),
+                time: self.time.elapsed(),
+                hashfull: self.tt.hashfull()
+            };
+            on_iteration(&result);
+
+            // There is nothing left to find once a mate is found or there are no moves.
+            let mate_found: bool = is_mate_score(score)
+                && mate_in(iteration as usize) <= score.abs();
+            let mate_limit: bool = limits.mate.is_some_and(|mate| {
+                score > 0 && is_mate_score(score) && mate_distance(score) <= mate as i32
+            });
+            if result.best_move.is_none() || mate_found || mate_limit || self.stopped
+                || self.time.soft_limit_reached() {
+                break;
+            }
+        }
+
+        self.shared_nodes.fetch_add(self.nodes % STOP_CHECK_INTERVAL, Ordering::Relaxed);
+        return result;
+    }
+
+    /// Searches the best lines of the root to the depth of the current iteration, one at a time.
+    /// Each line is searched with the first moves of the lines before it excluded, so it finds
+    /// the next best move. The lines of the previous iteration give the first move to try and the
+    /// center of the aspiration window. Also returns the score of the best line, and whether it
+    /// failed low. Without any moves, those come from searching the root.
+    fn search_lines(
+        &mut self,
+        board: &mut Board,
+        previous: &SearchResult
+    ) -> (Vec<PvLine>, i32, bool) {
+        let mut lines: Vec<(PvLine, bool)> = vec![];
+        let mut root: (i32, bool) = (0, false);
+        self.excluded.clear();
+
+        for index in 0..self.multipv {
+            let last: Option<&PvLine> = previous.lines.get(index);
+            self.pv[0] = last.map_or(vec![], |line| line.pv.clone());
+            self.seldepth = 0;
+            let center: i32 = last.map_or(previous.score, |line| line.score);
+            let (score, failed_low): (i32, bool) = self.aspiration_search(board, self.iteration,
+                center);
+            if index == 0 {
+                root = (score, failed_low);
+            }
+
+            // Every move may already be in a line.
+            let best_move: Move = match self.pv[0].first() {
+                Some(mv) if !self.stopped => mv.clone(),
+                _ => break
+            };
+            let line: PvLine = PvLine {
+                score,
+                depth: self.iteration,
+                seldepth: self.seldepth as u8,
+                pv: self.pv[0].clone()
+            };
+            lines.push((line, failed_low));
+            self.excluded.push(best_move);
+        }
+        self.excluded.clear();
+
+        // A later line can score higher than an earlier one when the search is unstable.
+        lines.sort_by_key(|(line, _)| -line.score);
+        let (score, failed_low): (i32, bool) = lines.first()
+            .map_or(root, |(line, failed_low)| (line.score, *failed_low));
+        return (lines.into_iter().map(|(line, _)| line).collect(), score, failed_low);
+    }
+
+    /// Counts a node, and checks every so many nodes if the search has to stop, because the stop
+    /// flag was set or a limit was reached. The first iteration is never stopped, so that there
+    /// is always a move.
+    fn count_node(&mut self) -> bool {
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(STOP_CHECK_INTERVAL) {
+            let total: u64 = self.shared_nodes.fetch_add(STOP_CHECK_INTERVAL, Ordering::Relaxed)
+                + STOP_CHECK_INTERVAL;
+            if !self.stopped && self.iteration > 1 {
+                self.stopped = self.stop.load(Ordering::Relaxed)
+                    || self.limits.nodes.is_some_and(|nodes| total >= nodes)
+                    || self.time.hard_limit_reached();
+            }
+        }
+        return self.stopped;
+    }
+
+    /// Searches the root with a narrow window around the score of the previous iteration, which
+    /// cuts off more nodes. If the score falls outside of the window, the window is widened on
+    /// that side and the root is searched again.
+    /// Also returns whether the score fell below the window at some point.
+    fn aspiration_search(&mut self, board: &mut Board, depth: u8, previous: i32) -> (i32, bool) {
+        let mut delta: i32 = ASPIRATION_WINDOW;
+        let mut failed_low: bool = false;
+        let (mut alpha, mut beta): (i32, i32) = (-INFINITY, INFINITY);
+        if self.config.aspiration_windows && depth >= 4 && !is_mate_score(previous) {
+            alpha = previous - delta;
+            beta = previous + delta;
+        }
+
+        loop {
+            let score: i32 = self.negamax(board, depth, 0, alpha, beta);
+            if self.stopped {
+                return (score, failed_low);
+            } else if score <= alpha {
+                alpha = (alpha - delta).max(-INFINITY);
+                failed_low = true;
+            } else if score >= beta {
+                beta = (beta + delta).min(INFINITY);
+            } else {
+                return (score, failed_low);
+            }
+            delta *= 2;
+        }
+    }
+
+    /// Stores a move followed by the principal variation of the next ply as the principal
+    /// variation of this ply.
+    fn update_pv(&mut self, ply: usize, mv: &Move) {
+        let (head, tail) = self.pv.split_at_mut(ply + 1);
+        let line: &mut Vec<Move> = &mut head[ply];
+        line.clear();
+        line.push(mv.clone());
+        line.extend_from_slice(&tail[0]);
+    }
+
+    fn negamax(
+        &mut self,
+        board: &mut Board,
+        depth: u8,
+        ply: usize,
+        mut alpha: i32,
+        mut beta: i32
+    ) -> i32 {
+        // The best move of the previous iteration is searched first at the root.
+        let mut hint: Option<Move> = if ply == 0 { self.pv[0].first().cloned() } else { None };
+        self.pv[ply].clear();
+        if is_draw(board, ply) {
+            return 0;
+        }
+        if depth == 0 || ply >= MAX_PLY {
+            return self.quiescence(board, ply, alpha, beta);
+        }
+        if self.count_node() {
+            return 0;
+        }
+        self.seldepth = self.seldepth.max(ply);
+
+        // Even a mate on the next move can't beat a mate that was already found closer to the
+        // root, so there is no need to search on if one was.
+        if ply > 0 {
+            alpha = alpha.max(mated_in(ply));
+            beta = beta.min(mate_in(ply + 1));
+            if alpha >= beta {
+                return alpha;
+            }
+        }
+
+        // A deep enough earlier search of the position may already decide this node. PV nodes
+        // are always searched, so that the principal variation isn't cut short.
+        let hash: u64 = board.hash();
+        let is_pv: bool = beta - alpha > 1;
+        if let Some(entry) = self.tt.probe(hash, ply) {
+            let cutoff: bool = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => entry.score >= beta,
+                Bound::Upper => entry.score <= alpha
+            };
+            if !is_pv && ply > 0 && entry.depth >= depth && cutoff {
+                return entry.score;
+            }
+            hint = hint.or(entry.best_move);
+        }
+        let original_alpha: i32 = alpha;
+
+        let mut moves: StagedMoves = StagedMoves::new(board, hint, self.ordering.killers.get(ply));
+        let in_check: bool = moves.state().checks != 0;
+        let config: SearchConfig = self.config;
+        let static_eval: i32 = if in_check { -INFINITY } else { self.evaluator.evaluate(board) };
+
+        // Reverse futility pruning: far enough above beta, a shallow search isn't going to lose
+        // enough to fail low.
+        let prunable: bool = !is_pv && !in_check && !is_mate_score(beta);
+        if config.reverse_futility && prunable && depth <= RFP_DEPTH
+            && static_eval - RFP_MARGIN * depth as i32 >= beta {
+            return static_eval;
+        }
+
+        // Null move pruning: if passing still fails high with a reduced search, a real move would
+        // too. Two null moves in a row would just search the same position shallower.
+        let after_null: bool = !board.history.data.last().unwrap().last_move.has_valid_flags();
+        if config.null_move && prunable && ply > 0 && !after_null && depth >= NULL_MOVE_DEPTH
+            && static_eval >= beta && has_non_pawn_material(board) {
+            let reduction: u8 = 3 + depth / 6;
+            board.make_null();
+            let score: i32 = -self.negamax(
+                board, depth.saturating_sub(reduction), ply + 1, -beta, -beta + 1
+            );
+            board.unmake_null();
+            if self.stopped {
+                return 0;
+            }
+            if score >= beta {
+                // A mate found after passing isn't a real mate.
+                return if is_mate_score(score) { beta } else { score };
+            }
+        }
+
+        // Futility pruning: quiet moves can't raise a hopeless shallow node above alpha.
+        let futile: bool = config.futility && prunable && (depth as usize) < FUTILITY_MARGINS.len()
+            && static_eval + FUTILITY_MARGINS[depth as usize] <= alpha;
+
+        let turn: Color = board.turn;
+        let countermove: Option<Move> = self.ordering.countermoves.get(board);
+        let mut quiets_tried: MoveList = MoveList::new();
+
+        let mut best: i32 = -INFINITY;
+        let mut best_move: Option<Move> = None;
+        let mut searched: usize = 0;
+
+        while let Some(mv) = moves.next_move(board, |mv| {
+            self.ordering.quiet_score(turn, countermove.as_ref(), mv)
+        }) {
+            if ply == 0 && self.excluded.contains(&mv) {
+                continue;
+            }
+            let quiet: bool = !mv.is_capture() && !mv.is_promotion();
+            let gives_check: bool = self.generator.gives_check(board, &mv);
+            if futile && searched > 0 && quiet && !gives_check {
+                continue;
+            }
+
+            // Checks are searched a ply deeper, so that forcing lines aren't cut short.
+            let extension: u8 = (config.check_extensions && gives_check) as u8;
+            let new_depth: u8 = depth - 1 + extension;
+
+            // Late move reductions: quiet moves that come late in the ordering are unlikely to be
+            // best, so they are searched shallower first.
+            let mut reduction: u8 = 0;
+            if config.late_move_reductions && depth >= LMR_DEPTH && searched >= LMR_MOVES
+                && quiet && !in_check && !gives_check {
+                reduction = (1 + depth / 6 + (searched / 8) as u8 - is_pv as u8).min(new_depth - 1);
+            }
+
+            board.make(&mv);
+            // The first move is searched with the full window, the rest only have to prove that
+            // they are worse, unless they turn out not to be.
+            let mut score: i32;
+            if searched == 0 {
+                score = -self.negamax(board, new_depth, ply + 1, -beta, -alpha);
+            } else {
+                score = -self.negamax(board, new_depth - reduction, ply + 1, -alpha - 1, -alpha);
+                if score > alpha && reduction > 0 {
+                    score = -self.negamax(board, new_depth, ply + 1, -alpha - 1, -alpha);
+                }
+                if score > alpha && score < beta {
+                    score = -self.negamax(board, new_depth, ply + 1, -beta, -alpha);
+                }
+            }
+            board.unmake();
+            if self.stopped {
+                return 0;
+            }
+            searched += 1;
+
+            if score > best {
+                best = score;
+                if score > alpha {
+                    alpha = score;
+                    self.update_pv(ply, &mv);
+                    best_move = Some(mv.clone());
+                }
+            }
+            if alpha >= beta {
+                // Quiet moves that cut off are likely to cut off elsewhere too.
+                if quiet {
+                    self.ordering.update_quiet(board, ply, depth, &mv, &quiets_tried);
+                }
+                break;
+            }
+            if quiet {
+                quiets_tried.push(mv);
+            }
+        }
+
+        if searched == 0 {
+            best = if in_check { mated_in(ply) } else { 0 };
+        }
+        let bound: Bound = if best >= beta {
+            Bound::Lower
+        } else if best > original_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        // The score of a root with excluded moves isn't the score of the position.
+        if ply > 0 || self.excluded.is_empty() {
+            self.tt.store(hash, depth, bound, best, best_move.as_ref(), ply);
+        }
+        return best;
+    }
+
+    /// Searches only the captures and promotions until the position is quiet, so that the
+    /// evaluation isn't taken in the middle of an exchange. The side to move may stand pat
+    /// instead of capturing, unless it is in check.
+    fn quiescence(&mut self, board: &mut Board, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        if self.count_node() {
+            return 0;
+        }
+        self.seldepth = self.seldepth.max(ply);
+        if ply >= MAX_PLY {
+            return self.evaluator.evaluate(board);
+        }
+        let state: BoardTables = self.generator.gen_board_tables(board);
+        let in_check: bool = state.checks != 0;
+
+        let mut best: i32 = -INFINITY;
+        if !in_check {
+            best = self.evaluator.evaluate(board);
+            if best >= beta {
+                return best;
+            }
+            alpha = alpha.max(best);
+        }
+
+        let mut list: MoveList = MoveList::new();
+        let mode: GenMode = if in_check { GenMode::Evasions } else { GenMode::Captures };
+        self.generator.gen_moves_mode(&mut list, board, &state, mode);
+        if in_check && list.size() == 0 {
+            return mated_in(ply);
+        }
+
+        let mut moves: ScoredMoves = ScoredMoves::new(list, |mv| mvv_lva(board, mv));
+        while let Some((mv, _)) = moves.pick_next() {
+            board.make(&mv);
+            let score: i32 = -self.quiescence(board, ply + 1, -beta, -alpha);
+            board.unmake();
+            if self.stopped {
+                return 0;
+            }
+
+            if score > best {
+                best = score;
+                if score > alpha {
+                    alpha = score;
+                    if alpha >= beta {
+                        break;
+                    }
+                }
+            }
+        }
+        return best;
+    }
+}
@@ -0,0 +1,523 @@
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::board::*;
+use crate::search::*;
+use crate::tests::helpers::*;
+
+fn search(fen: &str, depth: u8) -> (Board, SearchResult) {
+    return search_config(fen, depth, SearchConfig::default());
+}
+
+fn search_config(fen: &str, depth: u8, config: SearchConfig) -> (Board, SearchResult) {
+    let mut board: Board = Board::from_fen(fen).unwrap();
+    let mut searcher: Searcher = Searcher::new();
+    searcher.config = config;
+    let result: SearchResult = searcher.search(&mut board, depth);
+    assert_eq!(board.to_fen(), fen, "the board was changed by the search");
+    return (board, result);
+}
+
+/// Checks that the principal variation is a line of legal moves that starts with the best move.
+fn check_pv(board: &mut Board, result: &SearchResult) {
+    assert_eq!(result.pv.first(), result.best_move.as_ref());
+    for mv in &result.pv {
+        board.try_make(mv).unwrap();
+    }
+    for _ in &result.pv {
+        board.unmake();
+    }
+}
+
+#[test]
+fn test_mate_scores() {
+    assert_eq!(mate_distance(mate_in(1)), 1);
+    assert_eq!(mate_distance(mate_in(3)), 2);
+    assert_eq!(mate_distance(mated_in(2)), -1);
+    assert_eq!(mate_distance(mated_in(4)), -2);
+    assert!(is_mate_score(mate_in(40)) && is_mate_score(mated_in(40)));
+    assert!(!is_mate_score(2000));
+}
+
+#[test]
+fn test_mate_in_one() {
+    let (mut board, result) = search("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 3);
+    assert_eq!(result.best_move.as_ref().unwrap().to_long_algbr(), "a1a8");
+    assert_eq!(result.score, mate_in(1));
+    check_pv(&mut board, &result);
+}
+
+#[test]
+fn test_mate_in_two() {
+    // The rooks cut off the king one row at a time.
+    let (mut board, result) = search("3k4/8/8/8/8/8/R7/6KR w - - 0 1", 4);
+    assert_eq!(result.score, mate_in(3));
+    assert_eq!(result.pv.len(), 3);
+    check_pv(&mut board, &result);
+}
+
+#[test]
+fn test_getting_mated() {
+    // Black can only delay the back rank mate by one move.
+    let (mut board, result) = search("6k1/5p1p/6p1/8/8/8/1Q6/1R4K1 b - - 0 1", 4);
+    assert!(result.score < 0);
+    check_pv(&mut board, &result);
+}
+
+#[test]
+fn test_no_moves() {
+    let (_, result) = search("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", 3);
+    assert_eq!(result.best_move, None);
+    assert_eq!(result.score, 0);
+
+    let (_, result) = search("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1", 3);
+    assert_eq!(result.best_move, None);
+    assert_eq!(result.score, mated_in(0));
+}
+
+#[test]
+fn test_wins_material() {
+    // The queen is hanging.
+    let (mut board, result) = search("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1", 2);
+    assert_eq!(result.best_move.as_ref().unwrap().to_long_algbr(), "d2d5");
+    check_pv(&mut board, &result);
+
+    // Quiescence sees that the pawn is defended, so the queen doesn't take it.
+    let (_, result) = search("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1", 1);
+    assert_ne!(result.best_move.as_ref().unwrap().to_long_algbr(), "d1d5");
+}
+
+#[test]
+fn test_iterations() {
+    let mut board: Board = Board::from_fen(START_FEN).unwrap();
+    let mut depths: Vec<u8> = vec![];
+    let result: SearchResult = Searcher::new().search_with(&mut board, 3, |iteration| {
+        depths.push(iteration.depth);
+    });
+    assert_eq!(depths, [ 1, 2, 3 ]);
+    assert_eq!(result.depth, 3);
+    assert!(result.nodes > 0);
+    assert!(result.score.abs() < 100);
+    check_pv(&mut board, &result);
+}
+
+#[test]
+fn test_draws() {
+    // Taking the last black piece leaves too little material to mate.
+    let (_, result) = search("8/8/8/8/k7/8/3n4/4K3 w - - 0 1", 3);
+    assert_eq!(result.best_move.as_ref().unwrap().to_long_algbr(), "e1d2");
+    assert_eq!(result.score, 0);
+
+    // Once the knights are back home every position is a repetition.
+    let mut board: Board = Board::from_fen(START_FEN).unwrap();
+    for (i, algbr) in [ "g1f3", "g8f6", "f3g1", "f6g8", "g1f3", "g8f6" ].iter().enumerate() {
+        play(&mut board, &[ algbr ]);
+        assert_eq!(is_draw(&board, 1), i >= 3);
+    }
+}
+
+#[test]
+fn test_tt_probe_and_store() {
+    let tt: TranspositionTable = TranspositionTable::new(1);
+    assert_eq!(tt.size_mb(), 1);
+    let mv: Move = Move::new(Square::from_raw(52), Square::from_raw(36), DOUBLE_PAWN_PUSH);
+    assert_eq!(tt.probe(0x1234, 0), None);
+
+    tt.store(0x1234, 5, Bound::Lower, 42, Some(&mv), 3);
+    let entry: TTEntry = tt.probe(0x1234, 0).unwrap();
+    let expected: TTEntry = TTEntry {
+        best_move: Some(mv.clone()),
+        score: 42,
+        depth: 5,
+        bound: Bound::Lower
+    };
+    assert_eq!(entry, expected);
+    assert_eq!(tt.probe(0x1234 ^ (1 << 40), 0), None);
+
+    // Storing without a move keeps the old one.
+    tt.store(0x1234, 6, Bound::Upper, -7, None, 0);
+    let entry: TTEntry = tt.probe(0x1234, 0).unwrap();
+    assert_eq!((entry.best_move, entry.score, entry.depth), (Some(mv), -7, 6));
+
+    // A shallower search in the same search doesn't replace a deeper one, unless it is exact.
+    tt.store(0x1234, 2, Bound::Lower, 100, None, 0);
+    assert_eq!(tt.probe(0x1234, 0).unwrap().depth, 6);
+    tt.store(0x1234, 2, Bound::Exact, 100, None, 0);
+    assert_eq!(tt.probe(0x1234, 0).unwrap().depth, 2);
+
+    tt.clear();
+    assert_eq!(tt.probe(0x1234, 0), None);
+}
+
+#[test]
+fn test_tt_mate_scores() {
+    // A mate found 4 plies from the root is 2 plies away from the position at ply 2, and 2 plies
+    // away from wherever else the position is reached.
+    let tt: TranspositionTable = TranspositionTable::new(1);
+    tt.store(1, 3, Bound::Exact, mate_in(4), None, 2);
+    assert_eq!(tt.probe(1, 2).unwrap().score, mate_in(4));
+    assert_eq!(tt.probe(1, 6).unwrap().score, mate_in(8));
+    tt.store(2, 3, Bound::Exact, mated_in(5), None, 1);
+    assert_eq!(tt.probe(2, 3).unwrap().score, mated_in(7));
+}
+
+#[test]
+fn test_tt_replacement() {
+    // Every hash that is a multiple of the bucket count lands in the first bucket.
+    let tt: TranspositionTable = TranspositionTable::new(1);
+    let buckets: u64 = (1024 * 1024 / (BUCKET_SIZE * 16)) as u64;
+    let key = |i: u64| i * buckets;
+
+    for i in 0..BUCKET_SIZE as u64 {
+        tt.store(key(i), 10 - i as u8, Bound::Exact, 0, None, 0);
+    }
+    // The shallowest entry is replaced first.
+    tt.store(key(10), 9, Bound::Exact, 0, None, 0);
+    assert_eq!(tt.probe(key(3), 0), None);
+    assert!((0..3).chain([ 10 ]).all(|i| tt.probe(key(i), 0).is_some()));
+
+    // In a later search the old entries make way, even if they were searched deeper.
+    tt.new_search();
+    tt.store(key(11), 1, Bound::Exact, 0, None, 0);
+    assert_eq!(tt.probe(key(2), 0), None);
+    assert!(tt.probe(key(11), 0).is_some());
+}
+
+#[test]
+fn test_tt_hashfull() {
+    let tt: TranspositionTable = TranspositionTable::new(1);
+    assert_eq!(tt.hashfull(), 0);
+    for hash in 0..125 {
+        tt.store(hash, 1, Bound::Exact, 0, None, 0);
+    }
+    assert_eq!(tt.hashfull(), 125);
+    tt.new_search();
+    assert_eq!(tt.hashfull(), 0);
+
+    let mut searcher: Searcher = Searcher::new();
+    let mut board: Board = Board::from_fen(START_FEN).unwrap();
+    let result: SearchResult = searcher.search(&mut board, 4);
+    assert!(result.hashfull > 0 && result.hashfull <= searcher.tt().hashfull());
+    searcher.clear();
+    assert_eq!(searcher.tt().hashfull(), 0);
+}
+
+#[test]
+fn test_tt_search() {
+    // Searching again with the table filled gives the same result with fewer nodes.
+    let mut searcher: Searcher = Searcher::new();
+    let mut board: Board = Board::from_fen(
+        "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4"
+    ).unwrap();
+    let first: SearchResult = searcher.search(&mut board, 4);
+    let second: SearchResult = searcher.search(&mut board, 4);
+    assert_eq!(first.best_move, second.best_move);
+    assert!(second.nodes < first.nodes);
+    check_pv(&mut board, &second);
+}
+
+/// Gets the plain config with only the given feature turned on.
+fn only(feature: usize) -> SearchConfig {
+    let mut config: SearchConfig = SearchConfig::plain();
+    let toggles: [&mut bool; 6] = [
+        &mut config.null_move,
+        &mut config.late_move_reductions,
+        &mut config.futility,
+        &mut config.reverse_futility,
+        &mut config.check_extensions,
+        &mut config.aspiration_windows,
+    ];
+    *toggles.into_iter().nth(feature).unwrap() = true;
+    return config;
+}
+
+#[test]
+fn test_config_toggles() {
+    let configs = (0..6).map(only).chain([ SearchConfig::plain(), SearchConfig::default() ]);
+    for config in configs {
+        let (mut board, result) = search_config("3k4/8/8/8/8/8/R7/6KR w - - 0 1", 4, config);
+        assert_eq!(result.score, mate_in(3), "{:?}", config);
+        check_pv(&mut board, &result);
+
+        let (_, result) = search_config("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1", 4, config);
+        assert_eq!(result.best_move.unwrap().to_long_algbr(), "d2d5", "{:?}", config);
+    }
+}
+
+#[test]
+fn test_pruning_saves_nodes() {
+    let fen: &str = "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4";
+    let (_, plain) = search_config(fen, 4, SearchConfig::plain());
+    let (mut board, pruned) = search(fen, 4);
+    assert!(pruned.nodes * 2 < plain.nodes, "{} vs {} nodes", pruned.nodes, plain.nodes);
+    check_pv(&mut board, &pruned);
+}
+
+#[test]
+fn test_null_move_zugzwang() {
+    // With only the king and pawns left passing is often best, so null move pruning is skipped.
+    // Whoever has to move here loses the pawn race.
+    let fen: &str = "8/8/1k6/1p6/1P6/1K6/8/8 w - - 0 1";
+    assert!(!has_non_pawn_material(&Board::from_fen(fen).unwrap()));
+    let mut config: SearchConfig = SearchConfig::plain();
+    config.null_move = true;
+    let (_, with_null) = search_config(fen, 6, config);
+    let (_, without) = search_config(fen, 6, SearchConfig::plain());
+    assert_eq!(with_null.score, without.score);
+    assert_eq!(with_null.best_move, without.best_move);
+}
+
+#[test]
+fn test_time_manager() {
+    let ms = Duration::from_millis;
+
+    let manager: TimeManager = TimeManager::new(&SearchLimits::default(), Color::White);
+    assert_eq!((manager.soft_deadline(), manager.hard_deadline()), (None, None));
+
+    let manager: TimeManager = TimeManager::new(&SearchLimits::movetime(ms(1000)), Color::White);
+    assert_eq!(manager.soft_deadline(), Some(ms(1000) - MOVE_OVERHEAD));
+    assert_eq!(manager.hard_deadline(), Some(ms(1000) - MOVE_OVERHEAD));
+
+    // Each side uses its own clock.
+    let clock: SearchLimits = SearchLimits {
+        wtime: Some(ms(60030)),
+        btime: Some(ms(30)),
+        winc: Some(ms(1000)),
+        ..Default::default()
+    };
+    let mut manager: TimeManager = TimeManager::new(&clock, Color::White);
+    assert_eq!(manager.soft_deadline(), Some(ms(2000 + 750)));
+    assert_eq!(manager.hard_deadline(), Some(ms(11000)));
+    let manager_black: TimeManager = TimeManager::new(&clock, Color::Black);
+    assert_eq!(manager_black.hard_deadline(), Some(ms(0)));
+
+    // The soft deadline grows while the search is unstable, up to three times as long.
+    manager.update(true, false);
+    assert_eq!(manager.soft_deadline(), Some(ms(2750 * 3 / 2)));
+    for _ in 0..10 {
+        manager.update(true, true);
+    }
+    let soft: Duration = manager.soft_deadline().unwrap();
+    assert!(soft > ms(8000) && soft <= ms(8250), "{:?}", soft);
+    for _ in 0..20 {
+        manager.update(false, false);
+    }
+    assert!(manager.soft_deadline().unwrap() < ms(2760));
+
+    // Moves to go spreads the time over fewer moves, and infinite ignores the clock.
+    let manager: TimeManager = TimeManager::new(&SearchLimits {
+        movestogo: Some(4),
+        ..clock.clone()
+    }, Color::White);
+    assert_eq!(manager.soft_deadline(), Some(ms(15000 + 750)));
+    assert_eq!(manager.hard_deadline(), Some(ms(30000)));
+
+    // The last move before the time control and a large increment still leave half of the clock.
+    let manager: TimeManager = TimeManager::new(&SearchLimits {
+        movestogo: Some(1),
+        ..clock.clone()
+    }, Color::White);
+    assert_eq!(manager.soft_deadline(), Some(ms(30000)));
+    assert_eq!(manager.hard_deadline(), Some(ms(30000)));
+    let manager: TimeManager = TimeManager::new(&SearchLimits {
+        wtime: Some(ms(1030)),
+        winc: Some(ms(5000)),
+        ..Default::default()
+    }, Color::White);
+    assert_eq!(manager.soft_deadline(), Some(ms(500)));
+    assert_eq!(manager.hard_deadline(), Some(ms(500)));
+
+    let manager: TimeManager = TimeManager::new(&SearchLimits {
+        infinite: true,
+        ..clock
+    }, Color::White);
+    assert_eq!(manager.hard_deadline(), None);
+}
+
+#[test]
+fn test_limits() {
+    let fen: &str = "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4";
+    let mut board: Board = Board::from_fen(fen).unwrap();
+    let mut searcher: Searcher = Searcher::new();
+
+    let limits: SearchLimits = SearchLimits::nodes(5000);
+    let result: SearchResult = searcher.search_limits(&mut board, &limits, |_| {});
+    assert!(result.nodes < 5000 + STOP_CHECK_INTERVAL && result.best_move.is_some());
+
+    let start: Instant = Instant::now();
+    let limits: SearchLimits = SearchLimits::movetime(Duration::from_millis(200));
+    let result: SearchResult = searcher.search_limits(&mut board, &limits, |_| {});
+    assert!(start.elapsed() < Duration::from_millis(400), "{:?}", start.elapsed());
+    assert!(result.time <= start.elapsed());
+    check_pv(&mut board, &result);
+    assert_eq!(board.to_fen(), fen);
+
+    // The search stops as soon as it finds a mate short enough.
+    let mut board: Board = Board::from_fen("3k4/8/8/8/8/8/R7/6KR w - - 0 1").unwrap();
+    let limits: SearchLimits = SearchLimits { mate: Some(2), ..Default::default() };
+    let result: SearchResult = searcher.search_limits(&mut board, &limits, |_| {});
+    assert_eq!(result.score, mate_in(3));
+    assert_eq!(result.depth, 3);
+}
+
+#[test]
+fn test_mate_limit_after_other_searches() {
+    // What the move ordering learned in earlier searches decides which moves come late enough to
+    // be reduced, which must not hide the mate.
+    let fen: &str = "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4";
+    for nodes in (2000..40000).step_by(2500) {
+        let mut searcher: Searcher = Searcher::new();
+        let mut board: Board = Board::from_fen(fen).unwrap();
+        searcher.search_limits(&mut board, &SearchLimits::nodes(nodes), |_| {});
+
+        let mut board: Board = Board::from_fen("3k4/8/8/8/8/8/R7/6KR w - - 0 1").unwrap();
+        let limits: SearchLimits = SearchLimits { mate: Some(2), ..Default::default() };
+        let result: SearchResult = searcher.search_limits(&mut board, &limits, |_| {});
+        assert_eq!(result.score, mate_in(3), "after {} nodes", nodes);
+        assert_eq!(result.depth, 3);
+    }
+
+    // A mate that is further away than the limit isn't enough, and neither is no mate.
+    let mut searcher: Searcher = Searcher::new();
+    let mut board: Board = Board::from_fen("3k4/8/8/8/8/8/R7/6KR w - - 0 1").unwrap();
+    let limits: SearchLimits = SearchLimits { mate: Some(1), depth: Some(6), ..Default::default() };
+    let result: SearchResult = searcher.search_limits(&mut board, &limits, |_| {});
+    assert_eq!(result.score, mate_in(3));
+    assert!(result.depth > 1);
+}
+
+#[test]
+fn test_stop_flag() {
+    let mut board: Board = Board::from_fen(START_FEN).unwrap();
+    let mut searcher: Searcher = Searcher::new();
+    let stop = searcher.stop_flag();
+    let stopper = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(200));
+        stop.store(true, Ordering::Relaxed);
+    });
+
+    let limits: SearchLimits = SearchLimits { infinite: true, ..Default::default() };
+    let result: SearchResult = searcher.search_limits(&mut board, &limits, |_| {});
+    stopper.join().unwrap();
+    assert!(result.time < Duration::from_secs(2));
+    assert!(result.best_move.is_some());
+    check_pv(&mut board, &result);
+}
+
+#[test]
+fn test_threads() {
+    let mut searcher: Searcher = Searcher::new();
+    searcher.set_threads(4);
+    assert_eq!(searcher.threads(), 4);
+
+    let mut board: Board = Board::from_fen("3k4/8/8/8/8/8/R7/6KR w - - 0 1").unwrap();
+    let result: SearchResult = searcher.search(&mut board, 4);
+    assert_eq!(result.score, mate_in(3));
+    check_pv(&mut board, &result);
+
+    // The main thread reports the nodes of all threads.
+    let fen: &str = "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4";
+    let mut board: Board = Board::from_fen(fen).unwrap();
+    let mut reported: u64 = 0;
+    let limits: SearchLimits = SearchLimits::movetime(Duration::from_millis(300));
+    let result: SearchResult = searcher.search_limits(&mut board, &limits, |iteration| {
+        assert!(iteration.nodes >= reported);
+        reported = iteration.nodes;
+    });
+    assert!(result.nodes >= reported);
+    assert_eq!(board.to_fen(), fen);
+    check_pv(&mut board, &result);
+
+    searcher.set_threads(0);
+    assert_eq!(searcher.threads(), 1);
+    searcher.set_hash_size(2);
+    assert_eq!(searcher.tt().size_mb(), 2);
+    let result: SearchResult = searcher.search(&mut board, 3);
+    assert!(result.best_move.is_some());
+}
+
+fn search_multipv(fen: &str, depth: u8, lines: usize) -> (Board, SearchResult) {
+    let mut board: Board = Board::from_fen(fen).unwrap();
+    let mut searcher: Searcher = Searcher::new();
+    searcher.set_multipv(lines);
+    assert_eq!(searcher.multipv(), lines.max(1));
+    let result: SearchResult = searcher.search(&mut board, depth);
+    assert_eq!(board.to_fen(), fen, "the board was changed by the search");
+    return (board, result);
+}
+
+#[test]
+fn test_multipv() {
+    let (mut board, result) = search_multipv(START_FEN, 4, 3);
+    assert_eq!(result.lines.len(), 3);
+    assert_eq!(result.lines[0].pv, result.pv);
+    assert_eq!(result.lines[0].score, result.score);
+    for (i, line) in result.lines.iter().enumerate() {
+        assert_eq!(line.depth, 4);
+        assert!(line.seldepth >= line.depth);
+        for other in &result.lines[i + 1..] {
+            assert_ne!(line.pv[0], other.pv[0], "two lines start with the same move");
+            assert!(line.score >= other.score, "the lines aren't sorted");
+        }
+        for mv in &line.pv {
+            board.try_make(mv).unwrap();
+        }
+        for _ in &line.pv {
+            board.unmake();
+        }
+    }
+
+    // Only the first line is a mate.
+    let (_, result) = search_multipv("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 3, 2);
+    assert_eq!(result.lines[0].score, mate_in(1));
+    assert!(!is_mate_score(result.lines[1].score));
+    assert_ne!(result.lines[0].pv[0], result.lines[1].pv[0]);
+
+    // There can't be more lines than moves.
+    let (_, result) = search_multipv("7k/8/8/8/8/8/8/K7 w - - 0 1", 3, 5);
+    assert_eq!(result.lines.len(), 3);
+    let (_, result) = search_multipv("k7/1R6/1K6/8/8/8/8/8 b - - 0 1", 3, 2);
+    assert!(result.lines.is_empty() && result.best_move.is_none());
+    assert_eq!(result.score, 0);
+}
+
+#[test]
+fn test_multipv_result_is_first_line() {
+    // The score of the result is the score of its principal variation in every iteration, even
+    // when a later line scored higher than the first.
+    let fens: [&str; 3] = [
+        START_FEN,
+        "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"
+    ];
+    for fen in fens {
+        let mut board: Board = Board::from_fen(fen).unwrap();
+        let mut searcher: Searcher = Searcher::new();
+        searcher.set_multipv(4);
+        let result: SearchResult = searcher.search_with(&mut board, 5, |iteration| {
+            let first: &PvLine = &iteration.lines[0];
+            assert_eq!((iteration.score, &iteration.pv), (first.score, &first.pv), "{}", fen);
+            assert!(iteration.lines.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        });
+        assert_eq!(result.lines.len(), 4);
+    }
+}
+
+#[test]
+fn test_pv_notation() {
+    let (mut board, result) = search("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 3);
+    let generator: MoveGenerator = MoveGenerator::new();
+    assert_eq!(result.lines[0].to_uci(), "a1a8");
+    assert_eq!(result.lines[0].to_san(&generator, &mut board), "Ra8#");
+
+    let mut board: Board = Board::from_fen(START_FEN).unwrap();
+    let pv: Vec<Move> = play(&mut board, &[ "e2e4", "e7e5", "g1f3" ]);
+    for _ in &pv {
+        board.unmake();
+    }
+    let line: PvLine = PvLine { score: 0, depth: 3, seldepth: 3, pv };
+    assert_eq!(line.to_uci(), "e2e4 e7e5 g1f3");
+    assert_eq!(line.to_san(&generator, &mut board), "e4 e5 Nf3");
+    assert_eq!(board.to_fen(), START_FEN);
+}
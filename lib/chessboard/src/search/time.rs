@@ -0,0 +1,141 @@
+
+use std::time::{Duration, Instant};
+
+use crate::board::*;
+
+/// Time kept in reserve for the delay between the search stopping and the move reaching the
+/// clock.
+pub const MOVE_OVERHEAD: Duration = Duration::from_millis(30);
+/// The number of moves the remaining time is spread over when the time control doesn't say.
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+
+/// When a search has to stop, as given by the UCI go command. Every limit that is set applies, so
+/// the search stops at whichever is reached first. Without any limit the search goes on until it
+/// is stopped.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// Maximum depth in plies.
+    pub depth: Option<u8>,
+    /// Maximum number of nodes. The search may go slightly over it.
+    pub nodes: Option<u64>,
+    /// Exact time to search for.
+    pub movetime: Option<Duration>,
+    /// Time left on the clocks of white and black.
+    pub wtime: Option<Duration>,
+    pub btime: Option<Duration>,
+    /// Time added to the clocks of white and black after each move.
+    pub winc: Option<Duration>,
+    pub binc: Option<Duration>,
+    /// Moves left until the next time control, sudden death if not set.
+    pub movestogo: Option<u32>,
+    /// Stop once a mate in this many moves is found. The search doesn't prune or reduce while it
+    /// looks for one, and goes on past the depth of the mate until it is found.
+    pub mate: Option<u32>,
+    /// Ignore the clock and search until stopped.
+    pub infinite: bool
+}
+
+impl SearchLimits {
+    /// Limits that only stop the search at a depth.
+    pub fn depth(depth: u8) -> Self {
+        return SearchLimits { depth: Some(depth), ..Default::default() };
+    }
+
+    /// Limits that only stop the search after a number of nodes.
+    pub fn nodes(nodes: u64) -> Self {
+        return SearchLimits { nodes: Some(nodes), ..Default::default() };
+    }
+
+    /// Limits that only stop the search after some time.
+    pub fn movetime(movetime: Duration) -> Self {
+        return SearchLimits { movetime: Some(movetime), ..Default::default() };
+    }
+}
+
+/// Decides how long a search may take. The soft deadline is checked after each iteration of
+/// iterative deepening, since starting another iteration that won't finish is a waste. It grows
+/// when the search is unsure about the best move. The hard deadline stops the search wherever it
+/// is.
+#[derive(Clone, Debug)]
+pub struct TimeManager {
+    start: Instant,
+    soft: Option<Duration>,
+    hard: Option<Duration>,
+    /// The soft deadline is multiplied by this.
+    scale: f64
+}
+
+impl TimeManager {
+    /// Sets the deadlines for the side to move, starting the clock now.
+    pub fn new(limits: &SearchLimits, turn: Color) -> Self {
+        let mut manager: TimeManager = TimeManager {
+            start: Instant::now(),
+            soft: None,
+            hard: None,
+            scale: 1.0
+        };
+        if limits.infinite {
+            return manager;
+        }
+
+        let (time, inc): (Option<Duration>, Option<Duration>) = match turn {
+            Color::White => (limits.wtime, limits.winc),
+            Color::Black => (limits.btime, limits.binc)
+        };
+        if let Some(time) = time {
+            // Use an even share of the time left, plus most of the increment, while keeping
+            // enough on the clock for the moves after this one. No move gets more than half of
+            // the time left, even the last one before the time control or one with a large
+            // increment.
+            let available: Duration = time.saturating_sub(MOVE_OVERHEAD);
+            let moves_to_go: u32 = limits.movestogo.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+            let inc: Duration = inc.unwrap_or(Duration::ZERO);
+            let soft: Duration = (available / moves_to_go + inc * 3 / 4).min(available / 2);
+            manager.soft = Some(soft);
+            manager.hard = Some((soft * 4).min(available / 2));
+        }
+        if let Some(movetime) = limits.movetime {
+            let movetime: Duration = movetime.saturating_sub(MOVE_OVERHEAD);
+            manager.soft = Some(manager.soft.map_or(movetime, |soft| soft.min(movetime)));
+            manager.hard = Some(manager.hard.map_or(movetime, |hard| hard.min(movetime)));
+        }
+        return manager;
+    }
+
+    /// Gets the time since the search started.
+    pub fn elapsed(&self) -> Duration {
+        return self.start.elapsed();
+    }
+
+    pub fn soft_deadline(&self) -> Option<Duration> {
+        let hard: Duration = self.hard?;
+        return self.soft.map(|soft| soft.mul_f64(self.scale).min(hard));
+    }
+
+    pub fn hard_deadline(&self) -> Option<Duration> {
+        return self.hard;
+    }
+
+    /// Updates the soft deadline after an iteration. The search gets more time while the best
+    /// move keeps changing or after the score dropped, because the position is more complicated
+    /// than it looked. The extra time fades again once the search settles.
+    pub fn update(&mut self, best_move_changed: bool, failed_low: bool) {
+        self.scale = 1.0 + (self.scale - 1.0) / 2.0;
+        if best_move_changed {
+            self.scale += 0.5;
+        }
+        if failed_low {
+            self.scale += 0.5;
+        }
+    }
+
+    /// Returns true if there isn't enough time left to start another iteration.
+    pub fn soft_limit_reached(&self) -> bool {
+        return self.soft_deadline().is_some_and(|soft| self.elapsed() >= soft);
+    }
+
+    /// Returns true if the search has to stop right away.
+    pub fn hard_limit_reached(&self) -> bool {
+        return self.hard.is_some_and(|hard| self.elapsed() >= hard);
+    }
+}
@@ -0,0 +1,242 @@
+
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+
+use crate::search::*;
+
+/// Number of entries that share a bucket. A bucket of four fills a 64 byte cache line.
+pub const BUCKET_SIZE: usize = 4;
+/// Size of the table when none is given, in MB.
+pub const DEFAULT_SIZE_MB: usize = 16;
+
+/// What the stored score says about the real score of the position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    /// The score is exact, it was inside the search window.
+    Exact,
+    /// The real score is at least the stored score, the search failed high.
+    Lower,
+    /// The real score is at most the stored score, the search failed low.
+    Upper
+}
+
+/// The information stored about a position, as returned by a probe.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TTEntry {
+    pub best_move: Option<Move>,
+    /// Score from the perspective of the side to move, with mate scores relative to the ply it
+    /// was probed at.
+    pub score: i32,
+    pub depth: u8,
+    pub bound: Bound
+}
+
+/// An entry packed into two words. The key is stored xored with the data, so that an entry that
+/// was torn by two threads writing it at once doesn't match any position. The data holds the move
+/// in bits 0-15, the score in bits 16-31, the depth in bits 32-39, the bound in bits 40-41 (0 for
+/// an empty entry) and the age in bits 48-55.
+#[derive(Default)]
+struct Slot {
+    key: AtomicU64,
+    data: AtomicU64
+}
+
+impl Slot {
+    fn load(&self) -> (u64, u64) {
+        let data: u64 = self.data.load(Ordering::Relaxed);
+        return (self.key.load(Ordering::Relaxed) ^ data, data);
+    }
+
+    fn save(&self, hash: u64, data: u64) {
+        self.key.store(hash ^ data, Ordering::Relaxed);
+        self.data.store(data, Ordering::Relaxed);
+    }
+}
+
+fn data_move(data: u64) -> u16 {
+    return data as u16;
+}
+
+fn data_score(data: u64) -> i32 {
+    return (data >> 16) as u16 as i16 as i32;
+}
+
+fn data_depth(data: u64) -> u8 {
+    return (data >> 32) as u8;
+}
+
+fn data_bound(data: u64) -> Option<Bound> {
+    return match (data >> 40) & 0b11 {
+        1 => Some(Bound::Exact),
+        2 => Some(Bound::Lower),
+        3 => Some(Bound::Upper),
+        _ => None
+    };
+}
+
+fn data_age(data: u64) -> u8 {
+    return (data >> 48) as u8;
+}
+
+fn pack(best_move: u16, score: i32, depth: u8, bound: Bound, age: u8) -> u64 {
+    let bound: u64 = match bound {
+        Bound::Exact => 1,
+        Bound::Lower => 2,
+        Bound::Upper => 3
+    };
+    return best_move as u64 | (score as i16 as u16 as u64) << 16 | (depth as u64) << 32
+        | bound << 40 | (age as u64) << 48;
+}
+
+/// Mate scores are relative to the root during the search, but the same position can be reached
+/// at any ply, so they are stored relative to the position itself.
+fn score_to_tt(score: i32, ply: usize) -> i32 {
+    return if !is_mate_score(score) {
+        score
+    } else if score > 0 {
+        score + ply as i32
+    } else {
+        score - ply as i32
+    };
+}
+
+fn score_from_tt(score: i32, ply: usize) -> i32 {
+    return if !is_mate_score(score) {
+        score
+    } else if score > 0 {
+        score - ply as i32
+    } else {
+        score + ply as i32
+    };
+}
+
+/// A fixed size hash table of searched positions, keyed by zobrist hash. Positions map to a bucket
+/// of entries, so a new position only pushes out the least useful entry of its bucket: the one
+/// that was searched the least deep, with entries from earlier searches counting as less deep the
+/// older they are.
+///
+/// Every method takes a shared reference, so one table can be shared by several searching
+/// threads.
+pub struct TranspositionTable {
+    buckets: Vec<[Slot; BUCKET_SIZE]>,
+    /// Incremented for every new search, so that entries from old searches can be recognized.
+    age: AtomicU8
+}
+
+impl TranspositionTable {
+    /// Creates an empty table that takes up at most the given number of MB. The number of buckets
+    /// is rounded down to a power of two, but there is always at least one.
+    pub fn new(mb: usize) -> Self {
+        let bytes: usize = mb * 1024 * 1024;
+        let count: usize = (bytes / std::mem::size_of::<[Slot; BUCKET_SIZE]>()).max(1);
+        let mut buckets: Vec<[Slot; BUCKET_SIZE]> = Vec::new();
+        buckets.resize_with(1 << count.ilog2(), Default::default);
+        return TranspositionTable {
+            buckets,
+            age: AtomicU8::new(0)
+        };
+    }
+
+    /// Gets the size of the table in MB, rounded down.
+    pub fn size_mb(&self) -> usize {
+        return self.buckets.len() * std::mem::size_of::<[Slot; BUCKET_SIZE]>() / (1024 * 1024);
+    }
+
+    /// Empties the table.
+    pub fn clear(&self) {
+        for slot in self.buckets.iter().flatten() {
+            slot.save(0, 0);
+        }
+        self.age.store(0, Ordering::Relaxed);
+    }
+
+    /// Marks the start of a new search. Entries stored by earlier searches become easier to
+    /// replace.
+    pub fn new_search(&self) {
+        self.age.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn bucket(&self, hash: u64) -> &[Slot; BUCKET_SIZE] {
+        return &self.buckets[hash as usize & (self.buckets.len() - 1)];
+    }
+
+    /// Looks up a position, with mate scores adjusted to the ply it is probed at.
+    pub fn probe(&self, hash: u64, ply: usize) -> Option<TTEntry> {
+        for slot in self.bucket(hash) {
+            let (key, data): (u64, u64) = slot.load();
+            let bound: Bound = match data_bound(data) {
+                Some(bound) if key == hash => bound,
+                _ => continue
+            };
+            let best_move: u16 = data_move(data);
+            return Some(TTEntry {
+                best_move: if best_move != 0 { Some(Move::from_data(best_move)) } else { None },
+                score: score_from_tt(data_score(data), ply),
+                depth: data_depth(data),
+                bound
+            });
+        }
+        return None;
+    }
+
+    /// Stores the result of searching a position at some ply. An existing entry of the same
+    /// position is replaced, unless it was searched deeper in this search and the new score isn't
+    /// exact. It keeps its best move if there isn't a new one.
+    pub fn store(
+        &self,
+        hash: u64,
+        depth: u8,
+        bound: Bound,
+        score: i32,
+        best_move: Option<&Move>,
+        ply: usize
+    ) {
+        let age: u8 = self.age.load(Ordering::Relaxed);
+        let bucket: &[Slot; BUCKET_SIZE] = self.bucket(hash);
+        let mut best_move: u16 = best_move.map_or(0, |mv| mv.get_data());
+
+        let mut replace: &Slot = &bucket[0];
+        let mut replace_value: i32 = i32::MAX;
+        for slot in bucket {
+            let (key, data): (u64, u64) = slot.load();
+            if data_bound(data).is_some() && key == hash {
+                if bound != Bound::Exact && data_age(data) == age && data_depth(data) > depth {
+                    return;
+                }
+                if best_move == 0 {
+                    best_move = data_move(data);
+                }
+                replace = slot;
+                break;
+            }
+
+            // Empty entries are used first, then the shallowest after accounting for age.
+            let value: i32 = if data_bound(data).is_none() {
+                i32::MIN
+            } else {
+                data_depth(data) as i32 - 8 * age.wrapping_sub(data_age(data)) as i32
+            };
+            if value < replace_value {
+                replace = slot;
+                replace_value = value;
+            }
+        }
+
+        replace.save(hash, pack(best_move, score_to_tt(score, ply), depth, bound, age));
+    }
+
+    /// Gets how full the table is in permille, as UCI reports it. Only entries stored during the
+    /// current search count, and only the first thousand entries are looked at.
+    pub fn hashfull(&self) -> usize {
+        let age: u8 = self.age.load(Ordering::Relaxed);
+        let mut used: usize = 0;
+        let mut sampled: usize = 0;
+        for slot in self.buckets.iter().flatten().take(1000) {
+            let (_, data): (u64, u64) = slot.load();
+            if data_bound(data).is_some() && data_age(data) == age {
+                used += 1;
+            }
+            sampled += 1;
+        }
+        return used * 1000 / sampled;
+    }
+}
@@ -0,0 +1,33 @@
+use crate::board::*;
+use crate::generator::*;
+use crate::moves::*;
+
+/// Builds a move from a pair of algebraic squares.
+pub fn mv(from: &str, to: &str, flags: u16) -> Move {
+    return Move::new(from.parse().unwrap(), to.parse().unwrap(), flags);
+}
+
+/// Generates the legal moves of the position.
+pub fn legal_moves(board: &Board) -> MoveList {
+    let generator: MoveGenerator = MoveGenerator::new();
+    let mut moves: MoveList = MoveList::new();
+    let state: BoardTables = generator.gen_board_tables(board);
+    generator.gen_moves(&mut moves, board, &state);
+    return moves;
+}
+
+/// Finds the legal move given in long algebraic notation.
+pub fn uci_move(board: &Board, algbr: &str) -> Move {
+    return Move::from_uci_algbr(algbr, &legal_moves(board)).unwrap();
+}
+
+/// Plays moves given in long algebraic notation and returns them.
+pub fn play(board: &mut Board, moves: &[&str]) -> Vec<Move> {
+    let mut played: Vec<Move> = vec![];
+    for algbr in moves {
+        let mv: Move = uci_move(board, algbr);
+        board.make(&mv);
+        played.push(mv);
+    }
+    return played;
+}
@@ -1,2 +1,5 @@
 
-
+pub mod helpers;
+mod moves;
+mod pgn;
+mod types;
@@ -0,0 +1,200 @@
+
+use crate::board::*;
+use crate::moves::*;
+use crate::tests::helpers::*;
+
+const POSITIONS: [&str; 6] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+];
+
+fn san(fen: &str, algbr: &str) -> Result<String, String> {
+    let board: Board = Board::from_fen(fen).unwrap();
+    let moves: MoveList = legal_moves(&board);
+    return Move::from_short_algbr(algbr, &board, &moves)
+        .map(|mv| mv.to_long_algbr())
+        .map_err(|e| e.to_string());
+}
+
+#[test]
+fn test_short_algbr_round_trip() {
+    for fen in POSITIONS {
+        let mut board: Board = Board::from_fen(fen).unwrap();
+        let moves: MoveList = legal_moves(&board);
+
+        for i in 0..moves.size() {
+            let mv: &Move = moves.at(i);
+            let algbr: String = mv.to_short_algbr(&board, &moves);
+            let parsed = Move::from_short_algbr(&algbr, &board, &moves);
+            assert_eq!(parsed.as_ref().ok(), Some(mv), "{} in {}", algbr, fen);
+
+            // Check the replies as well to cover both colors.
+            board.make(mv);
+            let replies: MoveList = legal_moves(&board);
+            for j in 0..replies.size() {
+                let reply: &Move = replies.at(j);
+                let algbr: String = reply.to_short_algbr(&board, &replies);
+                let parsed = Move::from_short_algbr(&algbr, &board, &replies);
+                assert_eq!(parsed.as_ref().ok(), Some(reply), "{} in {}", algbr, board.to_fen());
+            }
+            board.unmake();
+        }
+    }
+}
+
+#[test]
+fn test_short_algbr_disambiguation() {
+    const FEN: &str = "2k5/8/8/8/4Q2Q/1N3N2/8/K6Q w - - 0 1";
+    let board: Board = Board::from_fen(FEN).unwrap();
+    let moves: MoveList = legal_moves(&board);
+    let mut names: Vec<String> = vec![];
+    for i in 0..moves.size() {
+        names.push(moves.at(i).to_short_algbr(&board, &moves));
+    }
+
+    for expected in [ "Nbd2", "Nfd2", "Qh4e1", "Q1e1", "Qee1", "Na5", "Nbd4", "Kb2" ] {
+        assert!(names.contains(&expected.to_string()), "missing {}", expected);
+    }
+
+    assert_eq!(san(FEN, "Nbd2"), Ok("b3d2".into()));
+    assert_eq!(san(FEN, "Nfd2"), Ok("f3d2".into()));
+    assert_eq!(san(FEN, "Qh4e1"), Ok("h4e1".into()));
+    assert_eq!(san(FEN, "Q1e1"), Ok("h1e1".into()));
+    assert_eq!(san(FEN, "Qee1"), Ok("e4e1".into()));
+    assert!(san(FEN, "Nd2").is_err());
+    assert!(san(FEN, "Qhe1").is_err());
+
+    const ROOKS: &str = "k7/8/8/8/8/4R3/8/4R1K1 w - - 0 1";
+    assert_eq!(san(ROOKS, "R1e2"), Ok("e1e2".into()));
+    assert_eq!(san(ROOKS, "R3e2"), Ok("e3e2".into()));
+    assert!(san(ROOKS, "Re2").is_err());
+}
+
+#[test]
+fn test_short_algbr_special_moves() {
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    assert_eq!(san(KIWIPETE, "O-O"), Ok("e1g1".into()));
+    assert_eq!(san(KIWIPETE, "0-0-0"), Ok("e1c1".into()));
+    assert_eq!(san(KIWIPETE, "Qxf6"), Ok("f3f6".into()));
+    assert_eq!(san(KIWIPETE, "dxe6!?"), Ok("d5e6".into()));
+    assert_eq!(san(KIWIPETE, "Nxf7+"), Ok("e5f7".into()));
+    assert_eq!(san(KIWIPETE, "Bxa6??"), Ok("e2a6".into()));
+
+    const PROMOS: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b kq - 0 1";
+    assert_eq!(san(PROMOS, "bxa1=Q"), Ok("b2a1q".into()));
+    assert_eq!(san(PROMOS, "b1=R"), Ok("b2b1r".into()));
+    assert_eq!(san(PROMOS, "b1B#"), Ok("b2b1b".into()));
+    assert!(san(PROMOS, "b1").is_err());
+
+    let board: Board = Board::from_fen(PROMOS).unwrap();
+    let moves: MoveList = legal_moves(&board);
+    let mv: Move = Move::from_short_algbr("bxa1N+", &board, &moves).unwrap();
+    assert_eq!(mv.get_flags(), KNIGHT_PROMO_CAPTURE);
+    assert!(san(PROMOS, "O-O").is_err());
+
+    const ENP: &str = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3";
+    assert_eq!(san(ENP, "exf6"), Ok("e5f6".into()));
+}
+
+#[test]
+fn test_short_algbr_errors() {
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    let board: Board = Board::from_fen(START).unwrap();
+    let moves: MoveList = legal_moves(&board);
+
+    for algbr in [ "", "Z4", "e9", "Nz3", "N", "Qe4=Q", "e4e5e6" ] {
+        match Move::from_short_algbr(algbr, &board, &moves) {
+            Err(PgnError::PgnMalformedError(_)) => (),
+            other => panic!("expected malformed error for {}: {:?}", algbr, other),
+        }
+    }
+
+    for algbr in [ "e5", "Nd2", "Ke2", "O-O", "exd3" ] {
+        match Move::from_short_algbr(algbr, &board, &moves) {
+            Err(PgnError::PgnInvalidMoveError(_)) => (),
+            other => panic!("expected invalid move error for {}: {:?}", algbr, other),
+        }
+    }
+}
+
+#[test]
+fn test_long_algbr_round_trip() {
+    for fen in POSITIONS {
+        let mut board: Board = Board::from_fen(fen).unwrap();
+        let moves: MoveList = legal_moves(&board);
+
+        for i in 0..moves.size() {
+            let mv: &Move = moves.at(i);
+            let algbr: String = mv.to_long_algbr();
+            assert_eq!(Move::from_uci_algbr(&algbr, &moves).ok().as_ref(), Some(mv), "{}", algbr);
+
+            board.make(mv);
+            let replies: MoveList = legal_moves(&board);
+            for j in 0..replies.size() {
+                let reply: &Move = replies.at(j);
+                let algbr: String = reply.to_long_algbr();
+                let parsed = Move::from_uci_algbr(&algbr, &replies);
+                assert_eq!(parsed.ok().as_ref(), Some(reply), "{} in {}", algbr, board.to_fen());
+            }
+            board.unmake();
+        }
+    }
+}
+
+#[test]
+fn test_long_algbr_flags() {
+    fn flags(fen: &str, algbr: &str) -> u16 {
+        let board: Board = Board::from_fen(fen).unwrap();
+        let moves: MoveList = legal_moves(&board);
+        return Move::from_uci_algbr(algbr, &moves).unwrap().get_flags();
+    }
+
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    assert_eq!(flags(KIWIPETE, "e1g1"), KING_SIDE_CASTLE);
+    assert_eq!(flags(KIWIPETE, "e1c1"), QUEEN_SIDE_CASTLE);
+    assert_eq!(flags(KIWIPETE, "a2a4"), DOUBLE_PAWN_PUSH);
+    assert_eq!(flags(KIWIPETE, "a2a3"), QUIET);
+    assert_eq!(flags(KIWIPETE, "f3f6"), CAPTURE);
+
+    const PROMOS: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b kq - 0 1";
+    assert_eq!(flags(PROMOS, "b2a1n"), KNIGHT_PROMO_CAPTURE);
+    assert_eq!(flags(PROMOS, "b2a1q"), QUEEN_PROMO_CAPTURE);
+    assert_eq!(flags(PROMOS, "b2b1r"), ROOK_PROMO);
+    assert_eq!(flags(PROMOS, "b2b1b"), BISHOP_PROMO);
+
+    const ENP: &str = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3";
+    assert_eq!(flags(ENP, "e5f6"), ENPASSANT);
+
+    let knight_promo: Move = Move::from_data(KNIGHT_PROMO | (8 << 6));
+    assert_eq!(knight_promo.to_long_algbr(), "a7a8n");
+}
+
+#[test]
+fn test_long_algbr_errors() {
+    const PROMOS: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b kq - 0 1";
+    let board: Board = Board::from_fen(PROMOS).unwrap();
+    let moves: MoveList = legal_moves(&board);
+
+    for algbr in [ "", "e2", "e2e4qq", "i2i4", "e0e1", "b2b1k", "b2b1Q" ] {
+        match Move::from_uci_algbr(algbr, &moves) {
+            Err(UciError::UciMalformedError(_)) => (),
+            other => panic!("expected malformed error for {}: {:?}", algbr, other),
+        }
+    }
+
+    match Move::from_uci_algbr("a1a1", &moves) {
+        Err(UciError::UciInvalidMoveError(_)) => (),
+        other => panic!("expected invalid move error: {:?}", other),
+    }
+
+    for algbr in [ "e8e6", "b2b1", "b2a1", "a8a7q", "e8g8" ] {
+        match Move::from_uci_algbr(algbr, &moves) {
+            Err(UciError::UciIllegalMoveError(mv)) => assert_eq!(mv.to_long_algbr(), algbr),
+            other => panic!("expected illegal move error for {}: {:?}", algbr, other),
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use crate::board::*;
+use crate::generator::*;
+use crate::moves::*;
+use crate::pgn::*;
+use crate::tests::helpers::*;
+
+const OPERA_GAME: &str = r#"[Event "Paris"]
+[Site "Paris FRA"]
+[Date "1858.??.??"]
+[White "Paul Morphy"]
+[Black "Duke Karl / Count Isouard"]
+[Result "1-0"]
+
+{ The Opera Game. } 1. e4 e5 2. Nf3 d6 3. d4 Bg4 $6 4. dxe5 Bxf3 5. Qxf3 dxe5
+6. Bc4 Nf6 7. Qb3 Qe7 8. Nc3 c6 9. Bg5 b5?! (9... Qb4 10. Qxb4 Bxb4 11. a3
+(11. O-O-O)) 10. Nxb5! cxb5 11. Bxb5+ Nbd7 12. O-O-O Rd8 13. Rxd7 Rxd7 14. Rd1
+Qe6 15. Bxd7+ Nxd7 ; White sacrifices the queen
+16. Qb8+!! Nxb8 17. Rd8# 1-0
+"#;
+
+/// Compares positions while ignoring the move clocks.
+fn fen_position(fen: &str) -> String {
+    return fen.split_whitespace().take(4).collect::<Vec<&str>>().join(" ");
+}
+
+fn error_location(pgn: &str) -> Option<(usize, usize)> {
+    return Board::from_pgn(pgn).err().and_then(|e| e.location());
+}
+
+#[test]
+fn test_pgn_opera_game() {
+    let game: Game = Board::from_pgn(OPERA_GAME).unwrap();
+
+    assert_eq!(game.tags.len(), 6);
+    assert_eq!(game.tag("White"), Some("Paul Morphy"));
+    assert_eq!(game.tag("Black"), Some("Duke Karl / Count Isouard"));
+    assert_eq!(game.tag("ECO"), None);
+    assert_eq!(game.result, "1-0");
+    assert_eq!(game.mainline.comments, vec!["The Opera Game."]);
+    assert_eq!(game.mainline.moves.len(), 33);
+
+    assert_eq!(
+        fen_position(&game.board.to_fen()),
+        "1n1Rkb1r/p4ppp/4q3/4p1B1/4P3/8/PPP2PPP/2K5 b k -"
+    );
+    assert!(game.board.to_fen().ends_with(" 17"));
+}
+
+#[test]
+fn test_pgn_annotations() {
+    let game: Game = Board::from_pgn(OPERA_GAME).unwrap();
+    let moves: &Vec<PgnMove> = &game.mainline.moves;
+
+    assert_eq!(moves[5].san, "Bg4");
+    assert_eq!(moves[5].nags, vec![6]);
+    assert_eq!(moves[17].san, "b5");
+    assert_eq!(moves[17].nags, vec![6]);
+    assert_eq!(moves[18].nags, vec![1]);
+    assert_eq!(moves[29].comments, vec!["White sacrifices the queen"]);
+    assert_eq!(moves[30].san, "Qb8+");
+    assert_eq!(moves[30].nags, vec![3]);
+}
+
+#[test]
+fn test_pgn_variations() {
+    let game: Game = Board::from_pgn(OPERA_GAME).unwrap();
+    let b5: &PgnMove = &game.mainline.moves[17];
+
+    assert_eq!(b5.variations.len(), 1);
+    let variation: &PgnLine = &b5.variations[0];
+    let sans: Vec<&str> = variation.moves.iter().map(|m| m.san.as_str()).collect();
+    assert_eq!(sans, vec!["Qb4", "Qxb4", "Bxb4", "a3"]);
+
+    // The nested variation replaces 11. a3.
+    assert_eq!(variation.moves[3].variations.len(), 1);
+    let nested: &PgnLine = &variation.moves[3].variations[0];
+    assert_eq!(nested.moves.len(), 1);
+    assert_eq!(nested.moves[0].san, "O-O-O");
+    assert_eq!(nested.moves[0].mv.to_long_algbr(), "e1c1");
+}
+
+#[test]
+fn test_pgn_check_suffixes() {
+    // 2. Qh5 doesn't give check, 3. Bc4 isn't mate and 4. Qxf7 is mate without saying so.
+    let game: Game = Board::from_pgn("1. e4 e5 2. Qh5+ Nc6 3. Bc4# Nf6 4. Qxf7 1-0").unwrap();
+    let sans: Vec<&str> = game.mainline.moves.iter().map(|m| m.san.as_str()).collect();
+    assert_eq!(sans, vec!["e4", "e5", "Qh5+", "Nc6", "Bc4#", "Nf6", "Qxf7"]);
+    assert_eq!(game.board.to_fen(),
+        "r1bqkb1r/pppp1Qpp/2n2n2/4p3/2B1P3/8/PPPP1PPP/RNB1K1NR b KQkq - 0 4");
+
+    // A suffix doesn't make an illegal move legal.
+    assert_eq!(error_location("1. e4 e5 2. Ke3+"), Some((1, 13)));
+    assert_eq!(error_location("1. e4 e5 2. Qxf7#"), Some((1, 13)));
+}
+
+#[test]
+fn test_pgn_setup() {
+    const PGN: &str = r#"[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/4P3/4K3 w - - 0 40"]
+
+40. e4 Kd7 41. e5 *"#;
+    let game: Game = Board::from_pgn(PGN).unwrap();
+
+    assert_eq!(game.start_fen, "4k3/8/8/8/8/8/4P3/4K3 w - - 0 40");
+    assert_eq!(game.result, "*");
+    assert_eq!(fen_position(&game.board.to_fen()), "8/3k4/8/4P3/8/8/8/4K3 b - -");
+    assert!(game.board.to_fen().ends_with(" 41"));
+}
+
+#[test]
+fn test_pgn_move_text_only() {
+    let game: Game = Board::from_pgn("1.e4 e5 2.Nf3 Nc6 3.Bb5 a6").unwrap();
+    assert!(game.tags.is_empty());
+    assert_eq!(game.result, "*");
+    assert_eq!(game.mainline_moves().len(), 6);
+
+    // Black to move at the start of a line is written with an ellipsis.
+    const PGN: &str = r#"[FEN "4k3/8/8/8/8/8/4P3/4K3 b - - 0 1"] 1... Kd7 2. e4 1/2-1/2"#;
+    let game: Game = Board::from_pgn(PGN).unwrap();
+    assert_eq!(game.mainline_moves().len(), 2);
+    assert_eq!(game.result, "1/2-1/2");
+}
+
+#[test]
+fn test_pgn_escapes() {
+    const PGN: &str = "[Event \"The \\\"Big\\\" one\"]\n% escaped line 1. d4\n1. e4 e5 0-1";
+    let game: Game = Board::from_pgn(PGN).unwrap();
+    assert_eq!(game.tag("Event"), Some("The \"Big\" one"));
+    assert_eq!(game.mainline_moves()[0].to_long_algbr(), "e2e4");
+    assert_eq!(game.result, "0-1");
+}
+
+#[test]
+fn test_read_games() {
+    let pgn: String = format!("{}\n[Event \"Second\"]\n1. d4 d5 *\n\n1. c4 1-0\n", OPERA_GAME);
+    let games: Vec<Game> = read_games(&pgn).unwrap();
+
+    assert_eq!(games.len(), 3);
+    assert_eq!(games[0].tag("Site"), Some("Paris FRA"));
+    assert_eq!(games[1].tag("Event"), Some("Second"));
+    assert_eq!(games[1].mainline_moves().len(), 2);
+    assert_eq!(games[2].result, "1-0");
+
+    assert!(Board::from_pgn(&pgn).is_err());
+}
+
+#[test]
+fn test_pgn_errors() {
+    // Illegal and unknown moves are reported where they are written.
+    assert_eq!(error_location("1. e4 e5\n2. Ke3"), Some((2, 4)));
+    assert_eq!(error_location("1. e4 e5 2. Qxf7"), Some((1, 13)));
+    assert_eq!(error_location("1. e4 Zz9"), Some((1, 7)));
+
+    // Malformed structure.
+    assert_eq!(error_location("[Event \"Paris\"\n1. e4"), Some((1, 1)));
+    assert_eq!(error_location("[Event \"Paris]"), Some((1, 8)));
+    assert_eq!(error_location("1. e4 { unterminated"), Some((1, 7)));
+    assert_eq!(error_location("1. e4 (1. d4"), Some((1, 7)));
+    assert_eq!(error_location("1. e4 e5 )"), Some((1, 10)));
+    assert_eq!(error_location("$1 1. e4"), Some((1, 1)));
+    assert_eq!(error_location("( 1. e4 )"), Some((1, 1)));
+    assert_eq!(error_location("1. e4 ?!? e5"), Some((1, 7)));
+    assert_eq!(error_location("1. e4 & e5"), Some((1, 7)));
+
+    // Bad starting positions are reported at the FEN tag.
+    let pgn: &str = "[Event \"?\"]\n[FEN \"8/8/8/8/8/8/8/8 w - - 0 1\"]\n*";
+    assert_eq!(error_location(pgn), Some((2, 1)));
+    assert!(matches!(
+        Board::from_pgn(pgn),
+        Err(PgnError::PgnLocatedError(_, _, ref e)) if matches!(**e, PgnError::PgnFenError(_))
+    ));
+}
+
+#[test]
+fn test_pgn_write_opera_game() {
+    let game: Game = Board::from_pgn(OPERA_GAME).unwrap();
+    let pgn: String = game.to_pgn();
+
+    assert!(pgn.starts_with("[Event \"Paris\"]\n[Site \"Paris FRA\"]\n"));
+    assert!(pgn.contains("[Result \"1-0\"]\n\n{The Opera Game.} 1. e4 e5 2. Nf3 d6 3. d4 Bg4 $6"));
+    let text: String = pgn.replace('\n', " ");
+    assert!(text.contains(" 9. Bg5 b5 $6 (9... Qb4 10. Qxb4 Bxb4 11. a3 (11. O-O-O)) 10. Nxb5 $1 "));
+    assert!(text.contains(" 15. Bxd7+ Nxd7 {White sacrifices the queen} 16. Qb8+ $3 Nxb8 17. Rd8# 1-0 "));
+    assert!(pgn.lines().all(|line| line.len() <= 80));
+
+    // Writing the game back out after reading it again gives the same text.
+    let reread: Game = Board::from_pgn(&pgn).unwrap();
+    assert_eq!(reread.to_pgn(), pgn);
+    assert_eq!(reread.board, game.board);
+}
+
+#[test]
+fn test_pgn_write_wraps_comments() {
+    let comment: String = vec!["word"; 40].join(" ");
+    let pgn: String = format!("1. e4 {{{}}} e5 *", comment);
+    let written: String = Board::from_pgn(&pgn).unwrap().to_pgn();
+
+    assert!(written.lines().count() > 1);
+    assert!(written.lines().all(|line| line.len() <= 80));
+    assert!(written.ends_with("word} 1... e5 *\n"));
+    assert_eq!(Board::from_pgn(&written).unwrap().mainline.moves[0].comments, vec![comment]);
+}
+
+#[test]
+fn test_pgn_write_closing_braces() {
+    let mut game: Game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    game.push(mv("a1", "a8", QUIET));
+    game.mainline.comments.push("}".to_string());
+    game.mainline.moves[0].comments.push("Mates {in one}, see} below".to_string());
+
+    let pgn: String = game.to_pgn();
+    assert!(pgn.ends_with("{} 1. Ra8# {Mates {in one, see below} *\n"), "{}", pgn);
+    let reread: Game = Board::from_pgn(&pgn).unwrap();
+    assert_eq!(reread.mainline.moves[0].comments, vec!["Mates {in one, see below"]);
+    assert_eq!(reread.to_pgn(), pgn);
+}
+
+#[test]
+fn test_pgn_write_from_position() {
+    const FEN: &str = "6k1/1p3ppp/8/8/8/8/5PPP/R5K1 b - - 0 30";
+    let mut game: Game = Game::from_fen(FEN).unwrap();
+    game.tags.push(("Event".to_string(), "Back \"rank\" mate".to_string()));
+
+    let moves: Vec<Move> = vec![
+        mv("b7", "b6", QUIET),
+        mv("a1", "a8", QUIET),
+    ];
+    for mv in moves {
+        game.push(mv);
+    }
+    game.result = "1-0".to_string();
+
+    assert_eq!(game.to_pgn(), concat!(
+        "[Event \"Back \\\"rank\\\" mate\"]\n",
+        "[SetUp \"1\"]\n",
+        "[FEN \"6k1/1p3ppp/8/8/8/8/5PPP/R5K1 b - - 0 30\"]\n",
+        "\n",
+        "30... b6 31. Ra8# 1-0\n"
+    ));
+}
+
+#[test]
+fn test_to_san_suffixes() {
+    let generator: MoveGenerator = MoveGenerator::new();
+
+    let mut board: Board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    assert_eq!(to_san(&generator, &mut board, &mv("a1", "a8", QUIET)), "Ra8#");
+    assert_eq!(to_san(&generator, &mut board, &mv("a1", "a7", QUIET)), "Ra7");
+
+    let mut board: Board = Board::from_fen("6k1/8/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    assert_eq!(to_san(&generator, &mut board, &mv("a1", "a8", QUIET)), "Ra8+");
+    assert_eq!(board.to_fen(), "6k1/8/8/8/8/8/8/R5K1 w - - 0 1");
+}
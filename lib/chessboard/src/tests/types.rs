@@ -0,0 +1,81 @@
+use crate::board::*;
+use crate::moves::*;
+
+#[test]
+fn test_square_parse_and_display() {
+    let e4: Square = "e4".parse().unwrap();
+    assert_eq!(e4.index(), 36);
+    assert_eq!(e4.row(), 4);
+    assert_eq!(e4.col(), 4);
+    assert_eq!(e4.bit(), 1u64 << 36);
+    assert_eq!(e4.to_string(), "e4");
+
+    assert_eq!("a8".parse::<Square>().unwrap().index(), 0);
+    assert_eq!("h1".parse::<Square>().unwrap().index(), 63);
+    for sq in Square::all() {
+        assert_eq!(sq.to_string().parse::<Square>(), Ok(sq));
+        assert_eq!(Square::from_row_col(sq.row(), sq.col()), Some(sq));
+    }
+
+    for bad in [ "", "e", "e44", "i1", "e9", "E4", "4e" ] {
+        assert!(matches!(bad.parse::<Square>(), Err(TypeParseError::SquareParseError(_))), "{}", bad);
+    }
+    assert_eq!(Square::new(64), None);
+    assert_eq!(Square::from_row_col(0, 8), None);
+}
+
+#[test]
+fn test_color_and_piece() {
+    assert_eq!(Color::White.opposite(), Color::Black);
+    assert_eq!(Color::White as usize, WHITE);
+    assert_eq!(Color::Black.index(), BLACK);
+    assert_eq!("w".parse::<Color>(), Ok(Color::White));
+    assert_eq!("black".parse::<Color>(), Ok(Color::Black));
+    assert!("x".parse::<Color>().is_err());
+    assert_eq!(Color::White.to_string(), "white");
+
+    assert_eq!(PieceType::Knight as usize, KNIGHT);
+    assert_eq!(PieceType::from_index(KING as u8), Some(PieceType::King));
+    assert_eq!(PieceType::from_index(EMPTY as u8), None);
+
+    let piece: Piece = "N".parse().unwrap();
+    assert_eq!(piece, Piece::new(PieceType::Knight, Color::White));
+    assert_eq!(Piece::new(PieceType::Queen, Color::Black).to_string(), "q");
+    assert!("x".parse::<Piece>().is_err());
+    assert!("Nn".parse::<Piece>().is_err());
+}
+
+#[test]
+fn test_board_typed_accessors() {
+    let board: Board = Board::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - - 0 1").unwrap();
+    let sq = |name: &str| name.parse::<Square>().unwrap();
+
+    assert_eq!(board.turn, Color::Black);
+    assert_eq!(board.enemy_color(), Color::White);
+    assert_eq!(board.piece_at(sq("e4")), Some(Piece::new(PieceType::Pawn, Color::White)));
+    assert_eq!(board.piece_at(sq("e8")), Some(Piece::new(PieceType::King, Color::Black)));
+    assert_eq!(board.type_at(sq("e1")), Some(PieceType::King));
+
+    // Empty squares have no type or color rather than looking like a black pawn.
+    assert_eq!(board.piece_at(sq("d4")), None);
+    assert_eq!(board.type_at(sq("a8")), None);
+    assert_eq!(board.color_at(sq("a8")), None);
+    assert_eq!(board.mailbox.get(sq("a8")), None);
+
+    assert_eq!(board.bitboard.pieces(PieceType::Pawn, Color::White), sq("e4").bit());
+    assert_eq!(board.bitboard.pieces(PieceType::Pawn, Color::Black), 0);
+    assert_eq!(board.bitboard.color_pieces(Color::Black), sq("e8").bit());
+    assert_eq!(board.bitboard.color_pieces(Color::White), sq("e1").bit() | sq("e4").bit());
+}
+
+#[test]
+fn test_move_squares() {
+    let mv: Move = Move::new("e7".parse().unwrap(), "d8".parse().unwrap(), QUEEN_PROMO_CAPTURE);
+    assert_eq!(mv.from_sq().to_string(), "e7");
+    assert_eq!(mv.to_sq().to_string(), "d8");
+    assert_eq!(mv.promo_piece(), Some(PieceType::Queen));
+    assert_eq!(mv.to_long_algbr(), "e7d8q");
+
+    let mv: Move = Move::new("g1".parse().unwrap(), "f3".parse().unwrap(), QUIET);
+    assert_eq!(mv.promo_piece(), None);
+}
@@ -0,0 +1,252 @@
+
+use crate::board;
+use std::fmt;
+use std::str::FromStr;
+
+/// The color of a player or a piece. The discriminants match the board::WHITE and board::BLACK
+/// indices, so a color can be cast straight to an index into the bitboards.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black = 0,
+    White = 1,
+}
+
+/// The type of a piece. The discriminants match the board::PAWN..board::KING indices.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PieceType {
+    Pawn = 0,
+    Knight = 1,
+    Bishop = 2,
+    Rook = 3,
+    Queen = 4,
+    King = 5,
+}
+
+/// A piece of some type and color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Piece {
+    pub ptype: PieceType,
+    pub color: Color
+}
+
+/// A square on the board. Squares are numbered from the top left corner as seen from white's
+/// perspective, so a8 is 0, h8 is 7 and h1 is 63.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Square(u8);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypeParseError {
+    SquareParseError(String),
+    ColorParseError(String),
+    PieceParseError(String),
+}
+
+impl fmt::Display for TypeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeParseError::SquareParseError(s) => write!(f, "invalid square: {}", s),
+            TypeParseError::ColorParseError(s) => write!(f, "invalid color: {}", s),
+            TypeParseError::PieceParseError(s) => write!(f, "invalid piece: {}", s),
+        }
+    }
+}
+
+impl Color {
+    pub fn opposite(self) -> Color {
+        return match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        };
+    }
+
+    pub fn index(self) -> usize {
+        return self as usize;
+    }
+
+    /// Gets the color for a raw index, where anything other than board::WHITE is black.
+    pub fn from_index(index: usize) -> Color {
+        return if index == board::WHITE { Color::White } else { Color::Black };
+    }
+
+    /// Gets the character used for the color in a fen string.
+    pub fn to_char(self) -> char {
+        return match self {
+            Color::White => 'w',
+            Color::Black => 'b',
+        };
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Color::White => write!(f, "white"),
+            Color::Black => write!(f, "black"),
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = TypeParseError;
+
+    /// Parses either the fen form ("w", "b") or the full name of a color.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "w" | "white" => Ok(Color::White),
+            "b" | "black" => Ok(Color::Black),
+            _ => Err(TypeParseError::ColorParseError(s.to_string())),
+        };
+    }
+}
+
+impl PieceType {
+    pub const ALL: [PieceType; 6] = [
+        PieceType::Pawn,
+        PieceType::Knight,
+        PieceType::Bishop,
+        PieceType::Rook,
+        PieceType::Queen,
+        PieceType::King,
+    ];
+
+    pub fn index(self) -> usize {
+        return self as usize;
+    }
+
+    /// Gets the piece type for a raw index. board::EMPTY (or anything else out of range) has no
+    /// piece type.
+    pub fn from_index(index: u8) -> Option<PieceType> {
+        return PieceType::ALL.get(index as usize).copied();
+    }
+
+    /// Gets the lowercase character used for the piece type in fen strings.
+    pub fn to_char(self) -> char {
+        return match self {
+            PieceType::Pawn => 'p',
+            PieceType::Knight => 'n',
+            PieceType::Bishop => 'b',
+            PieceType::Rook => 'r',
+            PieceType::Queen => 'q',
+            PieceType::King => 'k',
+        };
+    }
+
+    /// Parses a piece type from its fen character in either case.
+    pub fn from_char(c: char) -> Option<PieceType> {
+        return match c.to_ascii_lowercase() {
+            'p' => Some(PieceType::Pawn),
+            'n' => Some(PieceType::Knight),
+            'b' => Some(PieceType::Bishop),
+            'r' => Some(PieceType::Rook),
+            'q' => Some(PieceType::Queen),
+            'k' => Some(PieceType::King),
+            _ => None,
+        };
+    }
+}
+
+impl fmt::Display for PieceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl Piece {
+    pub fn new(ptype: PieceType, color: Color) -> Piece {
+        return Piece {
+            ptype,
+            color
+        };
+    }
+
+    /// Gets the fen character for the piece, uppercase for white and lowercase for black.
+    pub fn to_char(self) -> char {
+        let c: char = self.ptype.to_char();
+        return if self.color == Color::White { c.to_ascii_uppercase() } else { c };
+    }
+
+    /// Parses a piece from its fen character.
+    pub fn from_char(c: char) -> Option<Piece> {
+        let color: Color = if c.is_ascii_uppercase() { Color::White } else { Color::Black };
+        return PieceType::from_char(c).map(|ptype| Piece::new(ptype, color));
+    }
+}
+
+impl fmt::Display for Piece {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl FromStr for Piece {
+    type Err = TypeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        return match (chars.next().and_then(Piece::from_char), chars.next()) {
+            (Some(piece), None) => Ok(piece),
+            _ => Err(TypeParseError::PieceParseError(s.to_string())),
+        };
+    }
+}
+
+impl Square {
+    /// Creates a square from its index, if the index is on the board.
+    pub fn new(index: u8) -> Option<Square> {
+        return if index < 64 { Some(Square(index)) } else { None };
+    }
+
+    /// Creates a square from a row (0 is the eighth rank) and a column (0 is the a file).
+    pub fn from_row_col(row: u8, col: u8) -> Option<Square> {
+        return if row < 8 && col < 8 { Some(Square(row * 8 + col)) } else { None };
+    }
+
+    /// Wraps a raw square index that is already known to be on the board.
+    pub(crate) fn from_raw(index: u8) -> Square {
+        debug_assert!(index < 64);
+        return Square(index);
+    }
+
+    pub fn index(self) -> u8 {
+        return self.0;
+    }
+
+    pub fn row(self) -> u8 {
+        return self.0 / 8;
+    }
+
+    pub fn col(self) -> u8 {
+        return self.0 % 8;
+    }
+
+    /// Gets the bitboard with only this square set.
+    pub fn bit(self) -> u64 {
+        return 1u64 << self.0;
+    }
+
+    /// Iterates over all squares starting from a8.
+    pub fn all() -> impl Iterator<Item = Square> {
+        return (0..64).map(Square);
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", (b'a' + self.col()) as char, (b'8' - self.row()) as char)
+    }
+}
+
+impl FromStr for Square {
+    type Err = TypeParseError;
+
+    /// Parses a square in algebraic notation, e.g. "e4".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes: &[u8] = s.as_bytes();
+        if bytes.len() != 2 || !(b'a'..=b'h').contains(&bytes[0]) || !(b'1'..=b'8').contains(&bytes[1]) {
+            return Err(TypeParseError::SquareParseError(s.to_string()));
+        }
+        return Ok(Square((b'8' - bytes[1]) * 8 + (bytes[0] - b'a')));
+    }
+}